@@ -0,0 +1,148 @@
+//! Throughput and allocations of reading and writing a large synthetic log.
+//!
+//! Run with `cargo bench`, with `cargo bench --features intern` to compare key
+//! interning, and with `cargo bench --features bincode` to compare loading the binary
+//! encoding.
+
+use std::alloc::GlobalAlloc;
+use std::alloc::Layout;
+use std::alloc::System;
+use std::hint::black_box;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+use xes::Attribute;
+use xes::Key;
+use xes::Log;
+
+const TRACES: usize = 2_000;
+const EVENTS_PER_TRACE: usize = 20;
+const ITERATIONS: u32 = 10;
+
+/// System allocator that counts allocations and allocated bytes.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        System.dealloc(pointer, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn measure<T>(name: &str, bytes: usize, mut f: impl FnMut() -> T) {
+    black_box(f());
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let allocated_bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+    let mut total = Duration::ZERO;
+    for _ in 0..ITERATIONS {
+        let start = Instant::now();
+        black_box(f());
+        total += start.elapsed();
+    }
+    let mean = total / ITERATIONS;
+    let throughput = bytes as f64 / mean.as_secs_f64() / 1_000_000.0;
+    let allocations = (ALLOCATIONS.load(Ordering::Relaxed) - allocations) / ITERATIONS as usize;
+    let allocated_kilobytes =
+        (ALLOCATED_BYTES.load(Ordering::Relaxed) - allocated_bytes) / ITERATIONS as usize / 1_000;
+    println!(
+        "{name:<24} {mean:>12.2?} {throughput:>8.1} MB/s {allocations:>10} allocs {allocated_kilobytes:>8} kB"
+    );
+}
+
+/// `log` with a `long`, a `double` and a `boolean` added to every event.
+fn with_typed_values(mut log: Log) -> Log {
+    for (index, event) in log
+        .traces
+        .iter_mut()
+        .flat_map(|trace| &mut trace.events)
+        .enumerate()
+    {
+        event
+            .attributes
+            .insert(Key::from("index"), Attribute::Long(index as i64));
+        event.attributes.insert(
+            Key::from("cost:total"),
+            Attribute::Double(index as f64 / 8.0),
+        );
+        event
+            .attributes
+            .insert(Key::from("done"), Attribute::Boolean(index % 2 == 0));
+    }
+    log
+}
+
+/// Values rendered with an allocation per value, as the writer used to do.
+fn values_to_string(log: &Log) -> Vec<String> {
+    let mut values = Vec::new();
+    for event in log.all_events() {
+        for value in event.attributes.values() {
+            values.push(match value {
+                Attribute::String(value) | Attribute::DateTime(value) | Attribute::ID(value) => {
+                    value.to_string()
+                }
+                Attribute::Long(value) => value.to_string(),
+                Attribute::Double(value) => value.to_string(),
+                Attribute::Boolean(value) => value.to_string(),
+                Attribute::List(_) => String::new(),
+            });
+        }
+    }
+    values
+}
+
+fn main() {
+    let log = Log::synthetic(TRACES, EVENTS_PER_TRACE, 1);
+    let text = xes::write_string(&log);
+    println!(
+        "{TRACES} traces of {EVENTS_PER_TRACE} events, {} bytes, key interning {}",
+        text.len(),
+        if cfg!(feature = "intern") {
+            "on"
+        } else {
+            "off"
+        }
+    );
+    measure("read_str", text.len(), || {
+        xes::read_str(black_box(&text)).expect("synthetic log is valid")
+    });
+    measure("write_string", text.len(), || {
+        xes::write_string(black_box(&log))
+    });
+    #[cfg(feature = "bincode")]
+    {
+        let bytes = log.to_bincode().expect("synthetic log encodes");
+        println!("bincode encoding, {} bytes", bytes.len());
+        measure("from_bincode", bytes.len(), || {
+            Log::from_bincode(black_box(&bytes)).expect("encoded log decodes")
+        });
+    }
+
+    // As many children directly under `<log>` as the synthetic log has events.
+    let wide = xes::write_string(&Log::synthetic(TRACES * EVENTS_PER_TRACE, 1, 1));
+    println!("wide log of one-event traces, {} bytes", wide.len());
+    measure("read_str wide", wide.len(), || {
+        xes::read_str(black_box(&wide)).expect("synthetic log is valid")
+    });
+
+    let typed = with_typed_values(log);
+    let typed_length = xes::write_string(&typed).len();
+    println!("with long, double and boolean values, {typed_length} bytes");
+    measure("write_string typed", typed_length, || {
+        xes::write_string(black_box(&typed))
+    });
+    measure("values to_string", typed_length, || {
+        values_to_string(black_box(&typed))
+    });
+}
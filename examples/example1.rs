@@ -1,5 +1,5 @@
 fn main() {
-    let logs = xes::read("examples/inputs/example1.xml");
+    let logs = xes::read("examples/inputs/example1.xml").unwrap();
     println!("{:?}", logs);
-    xes::write(logs.get(0).unwrap(), "examples/outputs/result1.xml");
+    xes::write(logs.first().unwrap(), "examples/outputs/result1.xml").unwrap();
 }
@@ -0,0 +1,28 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use xes::WriteOptions;
+
+// Reading arbitrary input must never panic, and anything that reads must
+// survive being written and read again unchanged.
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(logs) = xes::read_str(text) else {
+        return;
+    };
+    let options = WriteOptions {
+        sort_attributes: true,
+        ..WriteOptions::default()
+    };
+    for log in &logs {
+        let written = xes::write_string_with_options(log, &options);
+        let reread = xes::read_str(&written).expect("written log must be readable");
+        assert_eq!(reread.len(), 1);
+        // Compared by their serialization, since `NaN` doubles never compare equal.
+        if reread[0] != *log {
+            assert_eq!(xes::write_string_with_options(&reread[0], &options), written);
+        }
+    }
+});
@@ -0,0 +1,14 @@
+use crate::Log;
+use crate::XesError;
+
+impl Log {
+    /// Compact binary encoding of this log, much faster to load than `XES`.
+    pub fn to_bincode(&self) -> Result<Vec<u8>, XesError> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    /// Decode a log encoded with [`Log::to_bincode`].
+    pub fn from_bincode(bytes: &[u8]) -> Result<Log, XesError> {
+        Ok(bincode::deserialize(bytes)?)
+    }
+}
@@ -0,0 +1,115 @@
+use std::fmt;
+
+/// Error produced while reading or writing `XES`.
+#[derive(Debug)]
+pub enum XesError {
+    /// Underlying I/O failure.
+    Io(std::io::Error),
+    /// Input is not well-formed XML.
+    Xml(roxmltree::Error),
+    /// Input is not well-formed XML, detected while streaming.
+    XmlStream(quick_xml::Error),
+    /// Element lacks a required attribute.
+    MissingAttribute {
+        element: String,
+        attribute: &'static str,
+    },
+    /// Attribute value does not match its declared type.
+    InvalidValue { key: String, value: String },
+    /// Integer value of a `long` attribute does not fit in 64 bits.
+    LongOutOfRange { key: String, value: String },
+    /// Log declares a version that is not a known `XES` version.
+    UnsupportedVersion(String),
+    /// Log repeats the identical declaration of the extension with this uri.
+    DuplicateExtension(String),
+    /// Element has more than one attribute with this key.
+    DuplicateKey(String),
+    /// Lists or logs are nested more than this many levels deep.
+    NestingTooDeep(usize),
+    /// Input is empty or contains no `<log>` element.
+    NoLogs,
+    /// Input contains this many logs where exactly one is expected.
+    MultipleLogs(usize),
+    /// Binary encoding or decoding failed.
+    #[cfg(feature = "bincode")]
+    Bincode(bincode::Error),
+}
+
+impl fmt::Display for XesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            XesError::Io(error) => write!(f, "I/O error: {error}"),
+            XesError::Xml(error) => write!(f, "XML error: {error}"),
+            XesError::XmlStream(error) => write!(f, "XML error: {error}"),
+            XesError::MissingAttribute { element, attribute } => {
+                write!(
+                    f,
+                    "element <{element}> is missing attribute \"{attribute}\""
+                )
+            }
+            XesError::InvalidValue { key, value } => {
+                write!(f, "invalid value \"{value}\" for attribute \"{key}\"")
+            }
+            XesError::LongOutOfRange { key, value } => {
+                write!(
+                    f,
+                    "value \"{value}\" of long attribute \"{key}\" is out of range"
+                )
+            }
+            XesError::UnsupportedVersion(version) => {
+                write!(f, "unsupported XES version \"{version}\"")
+            }
+            XesError::DuplicateExtension(uri) => {
+                write!(f, "extension \"{uri}\" is declared more than once")
+            }
+            XesError::DuplicateKey(key) => {
+                write!(f, "attribute \"{key}\" is given more than once")
+            }
+            XesError::NestingTooDeep(limit) => {
+                write!(f, "elements are nested more than {limit} levels deep")
+            }
+            XesError::NoLogs => write!(f, "input contains no log"),
+            XesError::MultipleLogs(count) => write!(f, "input contains {count} logs, not one"),
+            #[cfg(feature = "bincode")]
+            XesError::Bincode(error) => write!(f, "bincode error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for XesError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            XesError::Io(error) => Some(error),
+            XesError::Xml(error) => Some(error),
+            XesError::XmlStream(error) => Some(error),
+            #[cfg(feature = "bincode")]
+            XesError::Bincode(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for XesError {
+    fn from(error: std::io::Error) -> Self {
+        XesError::Io(error)
+    }
+}
+
+impl From<roxmltree::Error> for XesError {
+    fn from(error: roxmltree::Error) -> Self {
+        XesError::Xml(error)
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl From<bincode::Error> for XesError {
+    fn from(error: bincode::Error) -> Self {
+        XesError::Bincode(error)
+    }
+}
+
+impl From<quick_xml::Error> for XesError {
+    fn from(error: quick_xml::Error) -> Self {
+        XesError::XmlStream(error)
+    }
+}
@@ -0,0 +1,28 @@
+use crate::Log;
+use crate::Trace;
+use petgraph::graph::NodeIndex;
+use petgraph::Graph;
+use std::collections::HashMap;
+
+impl Log {
+    /// Directly-follows graph with a node per activity, weighted by its name, and an
+    /// edge per pair of activities, weighted by [`Log::directly_follows`] counts.
+    ///
+    /// Nodes and edges are added in name order, so equal logs give equal graphs.
+    pub fn to_dfg_graph(&self) -> Graph<String, usize> {
+        let mut activities: Vec<&str> = self.traces.iter().flat_map(Trace::activities).collect();
+        activities.sort_unstable();
+        activities.dedup();
+        let mut graph = Graph::with_capacity(activities.len(), 0);
+        let nodes: HashMap<&str, NodeIndex> = activities
+            .into_iter()
+            .map(|activity| (activity, graph.add_node(activity.to_owned())))
+            .collect();
+        let mut follows: Vec<_> = self.directly_follows().into_iter().collect();
+        follows.sort();
+        for ((from, to), count) in follows {
+            graph.add_edge(nodes[from.as_str()], nodes[to.as_str()], count);
+        }
+        graph
+    }
+}
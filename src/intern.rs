@@ -0,0 +1,27 @@
+use crate::ontology::Key;
+#[cfg(feature = "intern")]
+use std::collections::HashSet;
+
+/// Deduplicates attribute keys during a single read.
+#[derive(Default)]
+pub(crate) struct Interner {
+    #[cfg(feature = "intern")]
+    keys: HashSet<Key>,
+}
+
+impl Interner {
+    #[cfg(feature = "intern")]
+    pub(crate) fn intern(&mut self, key: &str) -> Key {
+        if let Some(key) = self.keys.get(key) {
+            return key.clone();
+        }
+        let key = Key::from(key);
+        self.keys.insert(key.clone());
+        key
+    }
+
+    #[cfg(not(feature = "intern"))]
+    pub(crate) fn intern(&mut self, key: &str) -> Key {
+        Key::from(key)
+    }
+}
@@ -0,0 +1,34 @@
+use crate::read_str;
+use crate::Log;
+use crate::XesError;
+use memmap2::Mmap;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+/// Transform `XES`-file to Rust representation, mapping the file into memory instead of
+/// reading it into a heap-allocated string.
+///
+/// This lowers peak memory for very large files, since only the parsed logs are
+/// allocated.
+///
+/// # Safety considerations
+///
+/// The mapping is read in place, so the file must not change while it is parsed.
+/// Another process writing to the file changes bytes the parser already holds as a
+/// validated `&str`, which is undefined behavior, and truncating it makes the pages
+/// past the new end unreadable, which on Unix kills the process with `SIGBUS`.
+/// Windows refuses to truncate a mapped file. Only use this for files that are not
+/// written to concurrently, and use [`read`](crate::read) otherwise.
+pub fn read_mmap<P: AsRef<Path>>(path: P) -> Result<Vec<Log>, XesError> {
+    let file = File::open(path)?;
+    if file.metadata()?.len() == 0 {
+        return Err(XesError::NoLogs);
+    }
+    // SAFETY: the mapping is only read and dropped before returning. That the file is
+    // not modified meanwhile is the caller's responsibility, as documented above.
+    let mapping = unsafe { Mmap::map(&file)? };
+    let text = std::str::from_utf8(&mapping)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    read_str(text)
+}
@@ -0,0 +1,55 @@
+use crate::Attribute;
+use crate::Log;
+use serde_json::json;
+use serde_json::Value;
+
+impl Log {
+    /// Minimal object-centric event log in the `OCEL` 2.0 JSON layout.
+    ///
+    /// Each trace becomes an object of type `case`, identified by its `concept:name` or
+    /// by its position, and each event with a `concept:name` becomes an event related
+    /// to its case. Other attributes and events without an activity are not exported.
+    pub fn to_ocel_json(&self) -> Value {
+        let mut objects = Vec::new();
+        let mut events = Vec::new();
+        let mut event_types: Vec<&str> = Vec::new();
+        for (trace_index, trace) in self.traces.iter().enumerate() {
+            let case = match trace.name() {
+                Some(name) => name.to_owned(),
+                None => format!("case-{trace_index}"),
+            };
+            for event in &trace.events {
+                let Some(activity) = event.name() else {
+                    continue;
+                };
+                if !event_types.contains(&activity) {
+                    event_types.push(activity);
+                }
+                let time = match event.attributes.get("time:timestamp") {
+                    Some(attribute @ Attribute::DateTime(value)) => attribute
+                        .as_datetime()
+                        .map_or_else(|| value.clone(), |datetime| datetime.to_rfc3339()),
+                    _ => String::new(),
+                };
+                events.push(json!({
+                    "id": format!("e{}", events.len() + 1),
+                    "type": activity,
+                    "time": time,
+                    "attributes": [],
+                    "relationships": [{ "objectId": case, "qualifier": "case" }],
+                }));
+            }
+            objects.push(json!({ "id": case, "type": "case", "attributes": [] }));
+        }
+        let event_types: Vec<Value> = event_types
+            .into_iter()
+            .map(|name| json!({ "name": name, "attributes": [] }))
+            .collect();
+        json!({
+            "objectTypes": [{ "name": "case", "attributes": [] }],
+            "eventTypes": event_types,
+            "objects": objects,
+            "events": events,
+        })
+    }
+}
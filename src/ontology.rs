@@ -1,11 +1,19 @@
 pub(crate) mod attribute;
+pub(crate) mod classifier;
 pub(crate) mod event;
 pub(crate) mod extension;
 pub(crate) mod log;
 pub(crate) mod trace;
 
 pub use attribute::Attribute;
+pub use attribute::Key;
+pub use classifier::Classifier;
 pub use event::Event;
 pub use extension::Extension;
+pub use extension::StandardExtension;
 pub use log::Log;
+pub use log::MissingResource;
+pub use log::TimezoneOffset;
+pub use trace::ActivityTiming;
 pub use trace::Trace;
+pub use trace::VariantKey;
@@ -1,8 +1,123 @@
+use chrono::DateTime;
+use chrono::FixedOffset;
+use chrono::NaiveDateTime;
+#[cfg(feature = "intern")]
+use std::borrow::Borrow;
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::hash::Hasher;
+#[cfg(feature = "intern")]
+use std::ops::Deref;
+#[cfg(feature = "intern")]
+use std::sync::Arc;
 
-#[derive(Debug)]
+/// Attribute key, a `String`.
+///
+/// With the `intern` feature this is instead a shared `Arc<str>` handle, so repeated
+/// keys such as `concept:name` are allocated once per read instead of once per attribute.
+#[cfg(not(feature = "intern"))]
+pub type Key = String;
+
+/// Attribute key, a shared `Arc<str>` handle so repeated keys such as `concept:name`
+/// are allocated once per read instead of once per attribute.
+///
+/// Without the `intern` feature this is a `String`.
+#[cfg(feature = "intern")]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct Key(Arc<str>);
+
+#[cfg(feature = "intern")]
+impl Key {
+    /// Key as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(feature = "intern")]
+impl Deref for Key {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(feature = "intern")]
+impl Borrow<str> for Key {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(feature = "intern")]
+impl AsRef<str> for Key {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(feature = "intern")]
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(feature = "intern")]
+impl From<&str> for Key {
+    fn from(key: &str) -> Self {
+        Key(Arc::from(key))
+    }
+}
+
+#[cfg(feature = "intern")]
+impl From<&String> for Key {
+    fn from(key: &String) -> Self {
+        Key(Arc::from(key.as_str()))
+    }
+}
+
+#[cfg(feature = "intern")]
+impl From<String> for Key {
+    fn from(key: String) -> Self {
+        Key(Arc::from(key))
+    }
+}
+
+#[cfg(feature = "intern")]
+impl From<Key> for String {
+    fn from(key: Key) -> Self {
+        key.0.as_ref().to_owned()
+    }
+}
+
+#[cfg(feature = "intern")]
+impl PartialEq<str> for Key {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+#[cfg(feature = "intern")]
+impl PartialEq<&str> for Key {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Attribute {
-    List(HashMap<String, Attribute>),
+    /// Nested attributes of a `<list>`, or of a `<container>` which is written back as `<list>`.
+    List(HashMap<Key, Attribute>),
     String(String),
     DateTime(String),
     Long(i64),
@@ -10,3 +125,255 @@ pub enum Attribute {
     Boolean(bool),
     ID(String),
 }
+
+impl Attribute {
+    /// `List` of the `(key, value)` pairs of `iter`, later pairs replacing earlier ones
+    /// with the same key.
+    pub fn list_from<K, I>(iter: I) -> Attribute
+    where
+        K: Into<Key>,
+        I: IntoIterator<Item = (K, Attribute)>,
+    {
+        Attribute::List(iter.into_iter().map(|(k, v)| (k.into(), v)).collect())
+    }
+
+    /// Name of the `XES` element this attribute is written as, such as `"long"`.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Attribute::List(_) => "list",
+            Attribute::String(_) => "string",
+            Attribute::DateTime(_) => "date",
+            Attribute::Long(_) => "long",
+            Attribute::Double(_) => "double",
+            Attribute::Boolean(_) => "boolean",
+            Attribute::ID(_) => "id",
+        }
+    }
+
+    /// Value of a `DateTime` attribute as a point in time.
+    ///
+    /// Values without a time zone offset are interpreted as UTC.
+    pub fn as_datetime(&self) -> Option<DateTime<FixedOffset>> {
+        match self {
+            Attribute::DateTime(value) => parse_datetime(value),
+            _ => None,
+        }
+    }
+
+    /// Value of a `String` attribute.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Attribute::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Value of an `ID` attribute.
+    pub fn as_id(&self) -> Option<&str> {
+        match self {
+            Attribute::ID(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Value of a `Long` or `Double` attribute as a float.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Attribute::Long(value) => Some(*value as f64),
+            Attribute::Double(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Order of two values of comparable types: numbers across `Long` and `Double`,
+    /// strings and ids lexically, datetimes chronologically and booleans with `false`
+    /// first. `None` for values of different kinds, lists and unparseable datetimes.
+    pub fn partial_cmp_value(&self, other: &Attribute) -> Option<Ordering> {
+        match (self, other) {
+            (Attribute::Long(a), Attribute::Long(b)) => Some(a.cmp(b)),
+            (
+                Attribute::Long(_) | Attribute::Double(_),
+                Attribute::Long(_) | Attribute::Double(_),
+            ) => self.as_f64()?.partial_cmp(&other.as_f64()?),
+            (Attribute::String(a), Attribute::String(b)) | (Attribute::ID(a), Attribute::ID(b)) => {
+                Some(a.cmp(b))
+            }
+            (Attribute::DateTime(_), Attribute::DateTime(_)) => {
+                Some(self.as_datetime()?.cmp(&other.as_datetime()?))
+            }
+            (Attribute::Boolean(a), Attribute::Boolean(b)) => Some(a.cmp(b)),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) fn parse_datetime(value: &str) -> Option<DateTime<FixedOffset>> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Some(datetime);
+    }
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+        .ok()
+        .map(|datetime| datetime.and_utc().fixed_offset())
+}
+
+pub(crate) fn get_many<'a>(
+    attributes: &'a HashMap<Key, Attribute>,
+    keys: &[&str],
+) -> Vec<Option<&'a Attribute>> {
+    keys.iter().map(|key| attributes.get(*key)).collect()
+}
+
+impl fmt::Display for Attribute {
+    /// Value as it appears in `XES`, lists render as `{key: value, ...}` in key order.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Attribute::List(list) => {
+                let mut entries: Vec<_> = list.iter().collect();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.into_iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{key}: {value}")?;
+                }
+                write!(f, "}}")
+            }
+            Attribute::String(value) | Attribute::DateTime(value) | Attribute::ID(value) => {
+                write!(f, "{value}")
+            }
+            Attribute::Long(value) => write!(f, "{value}"),
+            Attribute::Double(value) => write!(f, "{value}"),
+            Attribute::Boolean(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+/// Hashes `Double` values by their bits, so `NaN` values are never equal to each other
+/// even though values with identical bits hash identically.
+impl Hash for Attribute {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Attribute::List(list) => {
+                let mut entries: Vec<_> = list.iter().collect();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+                entries.hash(state);
+            }
+            Attribute::String(value) | Attribute::DateTime(value) | Attribute::ID(value) => {
+                value.hash(state)
+            }
+            Attribute::Long(value) => value.hash(state),
+            // `0.0 == -0.0`, so both must hash the same.
+            Attribute::Double(value) if *value == 0.0 => 0u64.hash(state),
+            Attribute::Double(value) => value.to_bits().hash(state),
+            Attribute::Boolean(value) => value.hash(state),
+        }
+    }
+}
+
+/// Leaf attributes of `attributes` and of their nested lists, keyed by `/`-separated
+/// key paths.
+pub(crate) fn flatten(attributes: &HashMap<Key, Attribute>) -> Vec<(String, &Attribute)> {
+    let mut leaves = Vec::new();
+    flatten_into(attributes, "", &mut leaves);
+    leaves
+}
+
+fn flatten_into<'a>(
+    attributes: &'a HashMap<Key, Attribute>,
+    prefix: &str,
+    leaves: &mut Vec<(String, &'a Attribute)>,
+) {
+    for (key, value) in attributes {
+        let path = format!("{prefix}{key}");
+        match value {
+            Attribute::List(list) => flatten_into(list, &format!("{path}/"), leaves),
+            _ => leaves.push((path, value)),
+        }
+    }
+}
+
+/// Replace prefix `old` of the keys of `attributes` and of their nested lists by `new`.
+pub(crate) fn rename_prefix(attributes: &mut HashMap<Key, Attribute>, old: &str, new: &str) {
+    *attributes = std::mem::take(attributes)
+        .into_iter()
+        .map(|(key, mut value)| {
+            if let Attribute::List(list) = &mut value {
+                rename_prefix(list, old, new);
+            }
+            let key = match key.split_once(':') {
+                Some((prefix, name)) if prefix == old => Key::from(format!("{new}:{name}")),
+                _ => key,
+            };
+            (key, value)
+        })
+        .collect();
+}
+
+/// Approximate number of bytes `attributes` take when written as `XES`.
+pub(crate) fn estimated_xml_size(attributes: &HashMap<Key, Attribute>) -> usize {
+    attributes
+        .iter()
+        .map(|(key, value)| {
+            // `<name key=""></name>` and ` value=""` for scalars
+            let markup = 2 * value.type_name().len() + 12 + key.len();
+            markup
+                + match value {
+                    Attribute::List(list) => estimated_xml_size(list),
+                    Attribute::String(value)
+                    | Attribute::DateTime(value)
+                    | Attribute::ID(value) => 9 + value.len(),
+                    Attribute::Long(value) => 9 + itoa::Buffer::new().format(*value).len(),
+                    Attribute::Double(value) => 9 + ryu::Buffer::new().format(*value).len(),
+                    Attribute::Boolean(value) => 13 + usize::from(!*value),
+                }
+        })
+        .sum()
+}
+
+/// Call `f` with every attribute of `attributes` and, after it, with the attributes of
+/// nested lists.
+pub(crate) fn visit<F: FnMut(&str, &Attribute)>(attributes: &HashMap<Key, Attribute>, f: &mut F) {
+    for (key, value) in attributes {
+        f(key, value);
+        if let Attribute::List(list) = value {
+            visit(list, f);
+        }
+    }
+}
+
+/// Mutable counterpart of [`visit`].
+pub(crate) fn visit_mut<F: FnMut(&str, &mut Attribute)>(
+    attributes: &mut HashMap<Key, Attribute>,
+    f: &mut F,
+) {
+    for (key, value) in attributes.iter_mut() {
+        f(key, value);
+        if let Attribute::List(list) = value {
+            visit_mut(list, f);
+        }
+    }
+}
+
+/// Replace the datetime values of `attributes` and of their nested lists by `f` of the
+/// point in time they denote, leaving values that do not parse unchanged.
+pub(crate) fn map_datetimes<F>(attributes: &mut HashMap<Key, Attribute>, f: &mut F)
+where
+    F: FnMut(DateTime<FixedOffset>) -> String,
+{
+    visit_mut(attributes, &mut |_, value| {
+        if let Attribute::DateTime(value) = value {
+            if let Some(datetime) = parse_datetime(value) {
+                *value = f(datetime);
+            }
+        }
+    });
+}
+
+/// `attributes` in key order.
+pub(crate) fn sorted(attributes: &HashMap<Key, Attribute>) -> Vec<(&Key, &Attribute)> {
+    let mut sorted: Vec<_> = attributes.iter().collect();
+    sorted.sort_by(|a, b| a.0.cmp(b.0));
+    sorted
+}
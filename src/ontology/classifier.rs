@@ -0,0 +1,48 @@
+/// Classifier declared by a `<classifier>` element, naming the attributes that
+/// together identify, for example, the activity of an event.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Classifier {
+    pub name: String,
+    /// Value of the `scope` attribute, `trace` or `event`, if given. Classifiers
+    /// without a scope apply to events.
+    pub scope: Option<String>,
+    /// Attribute keys of the classifier, such as `concept:name`.
+    pub keys: Vec<String>,
+}
+
+impl Classifier {
+    /// Keys of a `keys` attribute value, separated by whitespace. Keys containing
+    /// whitespace are enclosed in single quotes, such as `'my key'`.
+    pub(crate) fn parse_keys(text: &str) -> Vec<String> {
+        let mut keys = Vec::new();
+        let mut rest = text.trim_start();
+        while !rest.is_empty() {
+            let (key, remainder) = match rest.strip_prefix('\'') {
+                Some(quoted) => quoted.split_once('\'').unwrap_or((quoted, "")),
+                None => rest.split_once(char::is_whitespace).unwrap_or((rest, "")),
+            };
+            if !key.is_empty() {
+                keys.push(key.to_owned());
+            }
+            rest = remainder.trim_start();
+        }
+        keys
+    }
+
+    /// Value of the `keys` attribute for this classifier, see [`Classifier::parse_keys`].
+    pub(crate) fn keys_attribute(&self) -> String {
+        let keys: Vec<_> = self
+            .keys
+            .iter()
+            .map(|key| {
+                if key.contains(char::is_whitespace) {
+                    format!("'{key}'")
+                } else {
+                    key.clone()
+                }
+            })
+            .collect();
+        keys.join(" ")
+    }
+}
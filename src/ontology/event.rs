@@ -1,7 +1,124 @@
+use crate::ontology::attribute;
 use crate::ontology::Attribute;
+use crate::ontology::Key;
+use chrono::DateTime;
+use chrono::FixedOffset;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::sync::OnceLock;
 
-#[derive(Debug)]
+/// Event of a trace or log.
+///
+/// Besides its attributes an event holds a private cache of its parsed timestamp, so
+/// it cannot be built with a struct literal: use [`Event::new`] or
+/// [`Event::default`].
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Event {
-    pub attributes: HashMap<String, Attribute>,
+    pub attributes: HashMap<Key, Attribute>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    timestamp: TimestampCache,
+}
+
+/// Parsed `time:timestamp`, filled on first access together with a hash of the text
+/// it was parsed from.
+#[derive(Debug, Clone, Default)]
+struct TimestampCache(OnceLock<(u64, Option<DateTime<FixedOffset>>)>);
+
+/// The cache is derived data and never makes events unequal.
+impl PartialEq for TimestampCache {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+fn text_hash(value: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl Event {
+    pub fn new(attributes: HashMap<Key, Attribute>) -> Self {
+        Self {
+            attributes,
+            timestamp: TimestampCache::default(),
+        }
+    }
+
+    /// Value of the `time:timestamp` attribute.
+    ///
+    /// The value is parsed on first access and cached. If the attribute is changed
+    /// afterwards, which is detected by hashing its text, the new value is parsed on
+    /// every access instead.
+    pub fn timestamp(&self) -> Option<DateTime<FixedOffset>> {
+        let Attribute::DateTime(value) = self.attributes.get("time:timestamp")? else {
+            return None;
+        };
+        let hash = text_hash(value);
+        let (cached, timestamp) = self
+            .timestamp
+            .0
+            .get_or_init(|| (hash, attribute::parse_datetime(value)));
+        if *cached == hash {
+            *timestamp
+        } else {
+            attribute::parse_datetime(value)
+        }
+    }
+
+    /// Value of the `concept:name` attribute.
+    pub fn name(&self) -> Option<&str> {
+        self.attributes.get("concept:name")?.as_str()
+    }
+
+    /// Attributes for each of `keys`, in the same order.
+    pub fn get_many(&self, keys: &[&str]) -> Vec<Option<&Attribute>> {
+        attribute::get_many(&self.attributes, keys)
+    }
+
+    /// Value of the `org:resource` attribute.
+    pub fn resource(&self) -> Option<&str> {
+        self.attributes.get("org:resource")?.as_str()
+    }
+
+    /// Value of the `identity:id` attribute, kept exactly as read.
+    pub fn id(&self) -> Option<&str> {
+        self.attributes.get("identity:id")?.as_id()
+    }
+
+    /// Value of the `lifecycle:transition` attribute.
+    pub fn lifecycle_transition(&self) -> Option<&str> {
+        self.attributes.get("lifecycle:transition")?.as_str()
+    }
+
+    /// Leaf attributes including the contents of nested lists, with `/`-separated key
+    /// paths such as `cost:drivers/cost:type`.
+    pub fn attributes_flat(&self) -> impl Iterator<Item = (String, &Attribute)> {
+        attribute::flatten(&self.attributes).into_iter()
+    }
+
+    /// Attributes in key order, for deterministic iteration.
+    pub fn attributes_sorted(&self) -> Vec<(&Key, &Attribute)> {
+        attribute::sorted(&self.attributes)
+    }
+}
+
+/// Sum of the numeric `cost:amount` attributes of `events`, `None` if none has one.
+pub(crate) fn total_cost<'a>(events: impl IntoIterator<Item = &'a Event>) -> Option<f64> {
+    events
+        .into_iter()
+        .filter_map(|event| event.attributes.get("cost:amount")?.as_f64())
+        .fold(None, |total, amount| Some(total.unwrap_or(0.0) + amount))
+}
+
+/// Approximate number of bytes `events` take when written as `XES`.
+pub(crate) fn estimated_xml_size(events: &[Event]) -> usize {
+    events
+        .iter()
+        // `<event></event>`
+        .map(|event| 15 + attribute::estimated_xml_size(&event.attributes))
+        .sum()
 }
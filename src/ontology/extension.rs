@@ -1,6 +1,75 @@
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Extension {
     pub name: String,
     pub prefix: String,
     pub uri: String,
 }
+
+impl Extension {
+    /// Canonical declaration of the standard extension called `name`, e.g. `"Concept"`.
+    pub fn standard(name: &str) -> Option<Extension> {
+        StandardExtension::ALL
+            .into_iter()
+            .find(|extension| extension.name() == name)
+            .map(StandardExtension::extension)
+    }
+}
+
+/// Extensions defined by the `XES` standard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StandardExtension {
+    Concept,
+    Time,
+    Lifecycle,
+    Organizational,
+    Cost,
+}
+
+impl StandardExtension {
+    pub const ALL: [StandardExtension; 5] = [
+        StandardExtension::Concept,
+        StandardExtension::Time,
+        StandardExtension::Lifecycle,
+        StandardExtension::Organizational,
+        StandardExtension::Cost,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            StandardExtension::Concept => "Concept",
+            StandardExtension::Time => "Time",
+            StandardExtension::Lifecycle => "Lifecycle",
+            StandardExtension::Organizational => "Organizational",
+            StandardExtension::Cost => "Cost",
+        }
+    }
+
+    pub fn prefix(self) -> &'static str {
+        match self {
+            StandardExtension::Concept => "concept",
+            StandardExtension::Time => "time",
+            StandardExtension::Lifecycle => "lifecycle",
+            StandardExtension::Organizational => "org",
+            StandardExtension::Cost => "cost",
+        }
+    }
+
+    pub fn uri(self) -> &'static str {
+        match self {
+            StandardExtension::Concept => "http://www.xes-standard.org/concept.xesext",
+            StandardExtension::Time => "http://www.xes-standard.org/time.xesext",
+            StandardExtension::Lifecycle => "http://www.xes-standard.org/lifecycle.xesext",
+            StandardExtension::Organizational => "http://www.xes-standard.org/org.xesext",
+            StandardExtension::Cost => "http://www.xes-standard.org/cost.xesext",
+        }
+    }
+
+    pub fn extension(self) -> Extension {
+        Extension {
+            name: self.name().to_owned(),
+            prefix: self.prefix().to_owned(),
+            uri: self.uri().to_owned(),
+        }
+    }
+}
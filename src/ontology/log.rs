@@ -1,17 +1,42 @@
+use crate::ontology::attribute;
+use crate::ontology::event;
 use crate::ontology::Attribute;
+use crate::ontology::Classifier;
 use crate::ontology::Event;
 use crate::ontology::Extension;
+use crate::ontology::Key;
+use crate::ontology::StandardExtension;
 use crate::ontology::Trace;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::Utc;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::ops::RangeBounds;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Log {
     pub version: String,
     pub features: Vec<String>,
+    /// Additional attributes of the `<log>` element, such as `xmlns` declarations.
+    ///
+    /// They are written after `version` and `features`, namespace declarations first,
+    /// and entries named `version` or `features` are not written.
+    pub xml_attributes: Vec<(String, String)>,
     pub extensions: Vec<Extension>,
-    pub attributes: HashMap<String, Attribute>,
+    pub classifiers: Vec<Classifier>,
+    /// Attributes declared by `<global scope="trace">`, with their default values.
+    pub trace_globals: HashMap<Key, Attribute>,
+    /// Attributes declared by `<global scope="event">`, with their default values.
+    pub event_globals: HashMap<Key, Attribute>,
+    pub attributes: HashMap<Key, Attribute>,
     pub traces: Vec<Trace>,
     pub events: Vec<Event>,
+    /// Logs nested inside this log.
+    pub sub_logs: Vec<Log>,
 }
 
 impl Log {
@@ -19,10 +44,867 @@ impl Log {
         Self {
             version,
             features,
+            xml_attributes: Vec::new(),
             extensions: Vec::new(),
+            classifiers: Vec::new(),
+            trace_globals: HashMap::new(),
+            event_globals: HashMap::new(),
             attributes: HashMap::new(),
             traces: Vec::new(),
             events: Vec::new(),
+            sub_logs: Vec::new(),
         }
     }
+
+    /// Value of the log-level `concept:name` attribute.
+    pub fn name(&self) -> Option<&str> {
+        self.attributes.get("concept:name")?.as_str()
+    }
+
+    /// Copy of this log containing only the trace at `index`.
+    ///
+    /// Version, features, extensions, globals and log attributes are kept, so the
+    /// result is a self-contained log.
+    pub fn extract_trace(&self, index: usize) -> Option<Log> {
+        let trace = self.traces.get(index)?;
+        Some(self.with_traces(vec![trace.clone()]))
+    }
+
+    /// Copy of the version, features, extensions, globals and attributes of this log
+    /// with `traces` as its only content.
+    fn with_traces(&self, traces: Vec<Trace>) -> Log {
+        Self {
+            version: self.version.clone(),
+            features: self.features.clone(),
+            xml_attributes: self.xml_attributes.clone(),
+            extensions: self.extensions.clone(),
+            classifiers: self.classifiers.clone(),
+            trace_globals: self.trace_globals.clone(),
+            event_globals: self.event_globals.clone(),
+            attributes: self.attributes.clone(),
+            traces,
+            events: Vec::new(),
+            sub_logs: Vec::new(),
+        }
+    }
+
+    /// Copy of this log keeping only the traces whose [`Trace::start_time`] is within
+    /// `window`, such as `from..=to` or `from..to` to exclude the end.
+    ///
+    /// Events directly under the log and nested logs are not kept, as in
+    /// [`Log::extract_trace`].
+    pub fn filter_by_time_window(&self, window: impl RangeBounds<DateTime<Utc>>) -> Log {
+        let traces = self
+            .traces
+            .iter()
+            .filter(|trace| {
+                trace
+                    .start_time()
+                    .is_some_and(|start| window.contains(&start.with_timezone(&Utc)))
+            })
+            .cloned()
+            .collect();
+        self.with_traces(traces)
+    }
+
+    /// Declare a standard extension unless an extension with its uri is already declared.
+    pub fn add_standard_extension(&mut self, extension: StandardExtension) {
+        if !self.extensions.iter().any(|e| e.uri == extension.uri()) {
+            self.extensions.push(extension.extension());
+        }
+    }
+
+    /// Positions `(trace_index, event_index)` of events timestamped earlier than the
+    /// closest preceding timestamped event of the same trace.
+    pub fn check_timestamp_monotonicity(&self) -> Vec<(usize, usize)> {
+        let mut violations = Vec::new();
+        for (trace_index, trace) in self.traces.iter().enumerate() {
+            let mut previous = None;
+            for (event_index, event) in trace.events.iter().enumerate() {
+                let Some(timestamp) = event.timestamp() else {
+                    continue;
+                };
+                if previous.is_some_and(|previous| timestamp < previous) {
+                    violations.push((trace_index, event_index));
+                }
+                previous = Some(timestamp);
+            }
+        }
+        violations
+    }
+
+    /// Number of traces following each distinct activity sequence.
+    pub fn variant_frequencies(&self) -> HashMap<Vec<String>, usize> {
+        let mut frequencies = HashMap::new();
+        for trace in &self.traces {
+            let variant = trace.activities().into_iter().map(String::from).collect();
+            *frequencies.entry(variant).or_insert(0) += 1;
+        }
+        frequencies
+    }
+
+    /// Move the traces out of this log.
+    pub fn into_traces(self) -> Vec<Trace> {
+        self.traces
+    }
+
+    /// Apply [`Trace::broadcast_attribute`] to every trace.
+    pub fn broadcast_trace_attribute(&mut self, key: &str) {
+        for trace in &mut self.traces {
+            trace.broadcast_attribute(key);
+        }
+    }
+
+    /// Attributes for each of `keys`, in the same order.
+    pub fn get_many(&self, keys: &[&str]) -> Vec<Option<&Attribute>> {
+        attribute::get_many(&self.attributes, keys)
+    }
+
+    /// Events of every trace followed by the events directly under the log.
+    pub fn all_events(&self) -> impl Iterator<Item = &Event> {
+        self.traces
+            .iter()
+            .flat_map(|trace| &trace.events)
+            .chain(&self.events)
+    }
+
+    /// Number of events performed by each `org:resource`.
+    pub fn resource_frequencies(&self) -> HashMap<String, usize> {
+        let mut frequencies = HashMap::new();
+        for resource in self.all_events().filter_map(Event::resource) {
+            *frequencies.entry(resource.to_owned()).or_insert(0) += 1;
+        }
+        frequencies
+    }
+
+    /// Number of times work passes from one `org:resource` to the next within traces.
+    pub fn handover_of_work(&self, missing: MissingResource) -> HashMap<(String, String), usize> {
+        let mut handovers = HashMap::new();
+        for trace in &self.traces {
+            let mut previous: Option<&str> = None;
+            for event in &trace.events {
+                match event.resource() {
+                    Some(resource) => {
+                        if let Some(previous) = previous {
+                            let pair = (previous.to_owned(), resource.to_owned());
+                            *handovers.entry(pair).or_insert(0) += 1;
+                        }
+                        previous = Some(resource);
+                    }
+                    None if missing == MissingResource::BreakChain => previous = None,
+                    None => {}
+                }
+            }
+        }
+        handovers
+    }
+
+    /// Human-readable indented outline of the log, its traces, events and attributes.
+    ///
+    /// Each element shows its `concept:name` on its own line, other attributes follow
+    /// one level deeper in key order.
+    pub fn to_tree_string(&self) -> String {
+        let mut tree = format!("Log (v{})\n", self.version);
+        write_tree_attributes(&mut tree, &self.attributes, None, 1);
+        for trace in &self.traces {
+            write_tree_element(&mut tree, "Trace", &trace.attributes, 1);
+            for event in &trace.events {
+                write_tree_element(&mut tree, "Event", &event.attributes, 2);
+            }
+        }
+        for event in &self.events {
+            write_tree_element(&mut tree, "Event", &event.attributes, 1);
+        }
+        tree
+    }
+
+    /// Sum of the `cost:amount` of all events, `None` if no event has a cost.
+    pub fn total_cost(&self) -> Option<f64> {
+        event::total_cost(self.all_events())
+    }
+
+    /// Keep only the first `max_len` events of every trace.
+    pub fn truncate_traces(&mut self, max_len: usize) {
+        for trace in &mut self.traces {
+            trace.truncate_events(max_len);
+        }
+    }
+
+    /// Keep only the last `max_len` events of every trace.
+    pub fn keep_last_trace_events(&mut self, max_len: usize) {
+        for trace in &mut self.traces {
+            trace.keep_last_events(max_len);
+        }
+    }
+
+    /// Number of traces starting in each `bucket`-long interval, keyed by the interval
+    /// start. Traces start at their first timestamped event, intervals are aligned to
+    /// the Unix epoch.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bucket` is shorter than one millisecond.
+    pub fn arrival_rate(&self, bucket: Duration) -> BTreeMap<DateTime<Utc>, usize> {
+        let width = bucket.num_milliseconds();
+        assert!(width > 0, "bucket must be at least one millisecond");
+        let mut arrivals = BTreeMap::new();
+        for trace in &self.traces {
+            let Some(start) = trace.events.iter().find_map(Event::timestamp) else {
+                continue;
+            };
+            let millis = start.timestamp_millis().div_euclid(width) * width;
+            if let Some(bucket_start) = DateTime::from_timestamp_millis(millis) {
+                *arrivals.entry(bucket_start).or_insert(0) += 1;
+            }
+        }
+        arrivals
+    }
+
+    /// Log attributes overlaid with the attributes of the trace at `trace_index`, the
+    /// trace value winning when both define a key.
+    pub fn effective_trace_attributes(
+        &self,
+        trace_index: usize,
+    ) -> Option<HashMap<Key, Attribute>> {
+        let trace = self.traces.get(trace_index)?;
+        let mut attributes = self.attributes.clone();
+        attributes.extend(
+            trace
+                .attributes
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone())),
+        );
+        Some(attributes)
+    }
+
+    /// Empty `XES` 2.0 log declaring the concept extension, a valid starting point for
+    /// building logs programmatically.
+    pub fn minimal() -> Self {
+        let mut log = Self::new("2.0".to_owned(), Vec::new());
+        log.add_standard_extension(StandardExtension::Concept);
+        log
+    }
+
+    /// `XES` 2.0 log of `traces` traces with `events_per_trace` events each, for tests
+    /// and benchmarks.
+    ///
+    /// Traces are named `case 0`, `case 1` and so on. Events cycle through activities
+    /// `A` to `E` from a starting activity chosen per trace, and are timestamped from
+    /// `2020-01-01T00:00:00Z` in log order, one to sixty minutes apart. The same `seed`
+    /// always produces the same log.
+    pub fn synthetic(traces: usize, events_per_trace: usize, seed: u64) -> Self {
+        const ACTIVITIES: [&str; 5] = ["A", "B", "C", "D", "E"];
+        // SplitMix64, which is good enough for test data and needs no dependency.
+        let mut state = seed;
+        let mut random = move |bound: u64| {
+            state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            (z ^ (z >> 31)) % bound
+        };
+        let mut log = Self::minimal();
+        log.add_standard_extension(StandardExtension::Time);
+        let start = DateTime::<Utc>::from_timestamp(1_577_836_800, 0).expect("valid timestamp");
+        let mut minute = 0;
+        for trace_index in 0..traces {
+            let first_activity = random(ACTIVITIES.len() as u64) as usize;
+            let mut trace = Trace {
+                attributes: HashMap::from([(
+                    Key::from("concept:name"),
+                    Attribute::String(format!("case {trace_index}")),
+                )]),
+                events: Vec::with_capacity(events_per_trace),
+            };
+            for event_index in 0..events_per_trace {
+                let activity = ACTIVITIES[(first_activity + event_index) % ACTIVITIES.len()];
+                let timestamp = start + Duration::minutes(minute);
+                minute += 1 + random(60) as i64;
+                trace.events.push(Event::new(HashMap::from([
+                    (
+                        Key::from("concept:name"),
+                        Attribute::String(activity.to_owned()),
+                    ),
+                    (
+                        Key::from("time:timestamp"),
+                        Attribute::DateTime(timestamp.to_rfc3339()),
+                    ),
+                ])));
+            }
+            log.traces.push(trace);
+        }
+        log
+    }
+
+    /// Leaf attributes including the contents of nested lists, with `/`-separated key
+    /// paths such as `cost:drivers/cost:type`.
+    pub fn attributes_flat(&self) -> impl Iterator<Item = (String, &Attribute)> {
+        attribute::flatten(&self.attributes).into_iter()
+    }
+
+    /// Move the events directly under the log into new traces, one per distinct value
+    /// of attribute `key`, in order of first appearance.
+    ///
+    /// Each new trace is named after its value, events without `key` go to a trace
+    /// named `unknown`.
+    pub fn group_events_into_traces(&mut self, key: &str) {
+        let mut groups: Vec<(String, Vec<Event>)> = Vec::new();
+        let mut positions = HashMap::new();
+        for event in std::mem::take(&mut self.events) {
+            let group = match event.attributes.get(key) {
+                Some(value) => value.to_string(),
+                None => "unknown".to_owned(),
+            };
+            let position = *positions.entry(group.clone()).or_insert_with(|| {
+                groups.push((group, Vec::new()));
+                groups.len() - 1
+            });
+            groups[position].1.push(event);
+        }
+        for (name, events) in groups {
+            let mut attributes = HashMap::new();
+            attributes.insert(Key::from("concept:name"), Attribute::String(name));
+            self.traces.push(Trace { attributes, events });
+        }
+    }
+
+    /// Declared extension whose prefix is the part of `key` before its first colon.
+    ///
+    /// Keys without a colon or whose part before it is not a declared prefix, such as
+    /// `meta_concept:named_events`, belong to no extension.
+    pub fn extension_of_key(&self, key: &str) -> Option<&Extension> {
+        let (prefix, _) = key.split_once(':')?;
+        self.extensions.iter().find(|e| e.prefix == prefix)
+    }
+
+    /// Change the prefix of the extensions declaring prefix `old` to `new` and rewrite
+    /// attribute and classifier keys `old:name` to `new:name` throughout the log, its
+    /// traces, events, globals and nested logs.
+    ///
+    /// Keys of a log are only rewritten if the log declares an extension with prefix
+    /// `old`, see [`Log::extension_of_key`].
+    pub fn rename_prefix(&mut self, old: &str, new: &str) {
+        let mut declared = false;
+        for extension in &mut self.extensions {
+            if extension.prefix == old {
+                extension.prefix = new.to_owned();
+                declared = true;
+            }
+        }
+        if declared {
+            self.for_each_attribute_map(|attributes| {
+                attribute::rename_prefix(attributes, old, new)
+            });
+            let keys = self.classifiers.iter_mut().flat_map(|c| &mut c.keys);
+            for key in keys {
+                if let Some(name) = key.strip_prefix(old).and_then(|k| k.strip_prefix(':')) {
+                    *key = format!("{new}:{name}");
+                }
+            }
+        }
+        for sub_log in &mut self.sub_logs {
+            sub_log.rename_prefix(old, new);
+        }
+    }
+
+    /// Rewrite the values of datetime attributes throughout the log, its traces, events,
+    /// globals and nested logs in strftime `format`, such as `%Y-%m-%d`.
+    ///
+    /// Values that are not valid datetimes are left unchanged. Event timestamps are no
+    /// longer available if the new values do not include a time.
+    pub fn reformat_timestamps(&mut self, format: &str) {
+        self.for_each_attribute_map(|attributes| {
+            attribute::map_datetimes(attributes, &mut |datetime| {
+                datetime.format(format).to_string()
+            })
+        });
+        for sub_log in &mut self.sub_logs {
+            sub_log.reformat_timestamps(format);
+        }
+    }
+
+    /// Rewrite the values of datetime attributes throughout the log, its traces, events,
+    /// globals and nested logs without a time zone offset, such as
+    /// `2020-01-01T12:00:00`.
+    ///
+    /// `offset` chooses whether the local time or the UTC time is kept. Values without
+    /// an offset are read as UTC, so only [`TimezoneOffset::ConvertToUtc`] preserves
+    /// event timestamps as points in time.
+    pub fn strip_timezones(&mut self, offset: TimezoneOffset) {
+        self.for_each_attribute_map(|attributes| {
+            attribute::map_datetimes(attributes, &mut |datetime| {
+                let naive = match offset {
+                    TimezoneOffset::ConvertToUtc => datetime.naive_utc(),
+                    TimezoneOffset::Truncate => datetime.naive_local(),
+                };
+                naive.format("%Y-%m-%dT%H:%M:%S%.f").to_string()
+            })
+        });
+        for sub_log in &mut self.sub_logs {
+            sub_log.strip_timezones(offset);
+        }
+    }
+
+    /// Call `f` with the key and value of every attribute of the log, its globals,
+    /// traces, events, nested lists and nested logs, allowing values to be changed in
+    /// place.
+    pub fn map_attributes<F: FnMut(&str, &mut Attribute)>(&mut self, mut f: F) {
+        self.map_attributes_with(&mut f);
+    }
+
+    fn map_attributes_with<F: FnMut(&str, &mut Attribute)>(&mut self, f: &mut F) {
+        self.for_each_attribute_map(|attributes| attribute::visit_mut(attributes, f));
+        for sub_log in &mut self.sub_logs {
+            sub_log.map_attributes_with(f);
+        }
+    }
+
+    /// Part before the first colon of every attribute key of the log, its globals,
+    /// traces, events, nested lists and nested logs, such as `concept` and `time`, and
+    /// of every key of their classifiers.
+    ///
+    /// Prefixes are collected whether or not an extension declares them, to compare
+    /// against [`Log::extensions`].
+    pub fn referenced_prefixes(&self) -> HashSet<String> {
+        let mut prefixes = HashSet::new();
+        self.collect_prefixes(&mut prefixes);
+        prefixes
+    }
+
+    /// Remove the extensions whose prefix no attribute or classifier key of the log or
+    /// its nested logs uses, see [`Log::referenced_prefixes`].
+    pub fn prune_unused_extensions(&mut self) {
+        let prefixes = self.referenced_prefixes();
+        self.extensions
+            .retain(|extension| prefixes.contains(&extension.prefix));
+        for sub_log in &mut self.sub_logs {
+            sub_log.prune_unused_extensions();
+        }
+    }
+
+    fn collect_prefixes(&self, prefixes: &mut HashSet<String>) {
+        let traces = self.traces.iter().flat_map(|trace| {
+            std::iter::once(&trace.attributes).chain(trace.events.iter().map(|e| &e.attributes))
+        });
+        let maps = [&self.attributes, &self.trace_globals, &self.event_globals]
+            .into_iter()
+            .chain(traces)
+            .chain(self.events.iter().map(|event| &event.attributes));
+        for attributes in maps {
+            attribute::visit(attributes, &mut |key, _| {
+                if let Some((prefix, _)) = key.split_once(':') {
+                    if !prefixes.contains(prefix) {
+                        prefixes.insert(prefix.to_owned());
+                    }
+                }
+            });
+        }
+        let keys = self.classifiers.iter().flat_map(|c| &c.keys);
+        for (prefix, _) in keys.filter_map(|key| key.split_once(':')) {
+            if !prefixes.contains(prefix) {
+                prefixes.insert(prefix.to_owned());
+            }
+        }
+        for sub_log in &self.sub_logs {
+            sub_log.collect_prefixes(prefixes);
+        }
+    }
+
+    /// Apply `f` to the attributes of this log, its globals, traces and events, but not
+    /// of nested logs.
+    fn for_each_attribute_map<F: FnMut(&mut HashMap<Key, Attribute>)>(&mut self, mut f: F) {
+        f(&mut self.attributes);
+        f(&mut self.trace_globals);
+        f(&mut self.event_globals);
+        for trace in &mut self.traces {
+            f(&mut trace.attributes);
+            for event in &mut trace.events {
+                f(&mut event.attributes);
+            }
+        }
+        for event in &mut self.events {
+            f(&mut event.attributes);
+        }
+    }
+
+    /// Rough number of bytes this log takes when written as `XES`, to choose between
+    /// writing to memory and streaming.
+    ///
+    /// Escaping of special characters is not accounted for.
+    pub fn estimated_xml_size(&self) -> usize {
+        // `<log version="" features=""></log>`
+        let mut size = 34 + self.version.len() + self.features.join(",").len();
+        for (name, value) in &self.xml_attributes {
+            size += name.len() + value.len() + 4;
+        }
+        for extension in &self.extensions {
+            // `<extension name="" prefix="" uri=""></extension>`
+            size += 48 + extension.name.len() + extension.prefix.len() + extension.uri.len();
+        }
+        for classifier in &self.classifiers {
+            // `<classifier name="" keys=""></classifier>`
+            size += 41 + classifier.name.len() + classifier.keys_attribute().len();
+            size += classifier.scope.as_ref().map_or(0, |scope| 9 + scope.len());
+        }
+        for globals in [&self.trace_globals, &self.event_globals] {
+            if !globals.is_empty() {
+                // `<global scope="event"></global>`
+                size += 31 + attribute::estimated_xml_size(globals);
+            }
+        }
+        size += attribute::estimated_xml_size(&self.attributes);
+        for trace in &self.traces {
+            // `<trace></trace>`
+            size += 15 + attribute::estimated_xml_size(&trace.attributes);
+            size += event::estimated_xml_size(&trace.events);
+        }
+        size += event::estimated_xml_size(&self.events);
+        size += self
+            .sub_logs
+            .iter()
+            .map(Log::estimated_xml_size)
+            .sum::<usize>();
+        size
+    }
+
+    /// Distinct `lifecycle:transition` values of the events, such as `start` and
+    /// `complete`.
+    pub fn lifecycle_transitions(&self) -> HashSet<String> {
+        self.all_events()
+            .filter_map(Event::lifecycle_transition)
+            .map(String::from)
+            .collect()
+    }
+
+    /// Index of the trace with each `concept:name`, for repeated lookups by case id.
+    ///
+    /// Traces without a name are left out and a case id shared by several traces maps
+    /// to the first of them, see [`Log::duplicate_case_ids`].
+    pub fn build_case_index(&self) -> HashMap<String, usize> {
+        let mut index = HashMap::new();
+        for (position, trace) in self.traces.iter().enumerate() {
+            if let Some(name) = trace.name() {
+                index.entry(name.to_owned()).or_insert(position);
+            }
+        }
+        index
+    }
+
+    /// Index of the first trace whose `concept:name` is `name`.
+    ///
+    /// This scans the traces, use [`Log::build_case_index`] for repeated lookups.
+    pub fn trace_index_by_name(&self, name: &str) -> Option<usize> {
+        self.traces
+            .iter()
+            .position(|trace| trace.name() == Some(name))
+    }
+
+    /// Case ids shared by more than one trace, in order of their second occurrence.
+    pub fn duplicate_case_ids(&self) -> Vec<&str> {
+        let mut seen = HashSet::new();
+        let mut duplicates = Vec::new();
+        for name in self.traces.iter().filter_map(Trace::name) {
+            if !seen.insert(name) && !duplicates.contains(&name) {
+                duplicates.push(name);
+            }
+        }
+        duplicates
+    }
+
+    /// Number of traces starting with each `concept:name`.
+    pub fn start_activities(&self) -> HashMap<String, usize> {
+        boundary_frequencies(self.traces.iter().filter_map(Trace::first_event))
+    }
+
+    /// Number of traces ending with each `concept:name`.
+    pub fn end_activities(&self) -> HashMap<String, usize> {
+        boundary_frequencies(self.traces.iter().filter_map(Trace::last_event))
+    }
+
+    /// Number of distinct values of event attribute `key`, compared by their text.
+    pub fn value_cardinality(&self, key: &str) -> usize {
+        self.all_events()
+            .filter_map(|event| event.attributes.get(key))
+            .map(Attribute::to_string)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// Number of distinct values of every event attribute key, compared by their text.
+    pub fn attribute_profile(&self) -> HashMap<String, usize> {
+        let mut values: HashMap<&str, HashSet<String>> = HashMap::new();
+        for event in self.all_events() {
+            for (key, value) in &event.attributes {
+                values.entry(key).or_default().insert(value.to_string());
+            }
+        }
+        values
+            .into_iter()
+            .map(|(key, values)| (key.to_owned(), values.len()))
+            .collect()
+    }
+
+    /// Number of times each activity is directly followed by another within traces,
+    /// skipping events without a `concept:name`.
+    pub fn directly_follows(&self) -> HashMap<(String, String), usize> {
+        let mut follows = HashMap::new();
+        for trace in &self.traces {
+            for pair in trace.activities().windows(2) {
+                let pair = (pair[0].to_owned(), pair[1].to_owned());
+                *follows.entry(pair).or_insert(0) += 1;
+            }
+        }
+        follows
+    }
+
+    /// Activities that directly follow themselves, with the number of times they do.
+    pub fn self_loops(&self) -> HashMap<String, usize> {
+        self.directly_follows()
+            .into_iter()
+            .filter(|((from, to), _)| from == to)
+            .map(|((activity, _), count)| (activity, count))
+            .collect()
+    }
+
+    /// Whether any event has attribute `key`, stopping at the first that does.
+    pub fn any_event_has(&self, key: &str) -> bool {
+        self.all_events()
+            .any(|event| event.attributes.contains_key(key))
+    }
+
+    /// Whether every event has attribute `key`, stopping at the first that does not.
+    ///
+    /// `true` for a log without events.
+    pub fn all_events_have(&self, key: &str) -> bool {
+        self.all_events()
+            .all(|event| event.attributes.contains_key(key))
+    }
+
+    /// Copy of this log keeping its structure and only the `concept:name` and
+    /// `lifecycle:transition` attributes, for sharing control flow without data.
+    ///
+    /// Only the extensions with prefix `concept` or `lifecycle` are kept, and only the
+    /// classifiers using no other keys.
+    pub fn skeleton(&self) -> Log {
+        const KEPT: [&str; 2] = ["concept:name", "lifecycle:transition"];
+        let keep = |attributes: &HashMap<Key, Attribute>| -> HashMap<Key, Attribute> {
+            attributes
+                .iter()
+                .filter(|(key, _)| KEPT.contains(&&***key))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect()
+        };
+        let skeleton_event = |event: &Event| Event::new(keep(&event.attributes));
+        Log {
+            version: self.version.clone(),
+            features: self.features.clone(),
+            xml_attributes: self.xml_attributes.clone(),
+            extensions: self
+                .extensions
+                .iter()
+                .filter(|e| matches!(e.prefix.as_str(), "concept" | "lifecycle"))
+                .cloned()
+                .collect(),
+            classifiers: self
+                .classifiers
+                .iter()
+                .filter(|c| c.keys.iter().all(|key| KEPT.contains(&key.as_str())))
+                .cloned()
+                .collect(),
+            trace_globals: keep(&self.trace_globals),
+            event_globals: keep(&self.event_globals),
+            attributes: keep(&self.attributes),
+            traces: self
+                .traces
+                .iter()
+                .map(|trace| Trace {
+                    attributes: keep(&trace.attributes),
+                    events: trace.events.iter().map(skeleton_event).collect(),
+                })
+                .collect(),
+            events: self.events.iter().map(skeleton_event).collect(),
+            sub_logs: self.sub_logs.iter().map(Log::skeleton).collect(),
+        }
+    }
+
+    /// Attributes in key order, for deterministic iteration.
+    pub fn attributes_sorted(&self) -> Vec<(&Key, &Attribute)> {
+        attribute::sorted(&self.attributes)
+    }
+
+    /// Indices of the traces whose last event's `concept:name` is not one of
+    /// `end_activities`, such as cases still in progress. Empty traces are included.
+    pub fn incomplete_traces(&self, end_activities: &[&str]) -> Vec<usize> {
+        self.traces
+            .iter()
+            .enumerate()
+            .filter(|(_, trace)| {
+                trace
+                    .last_event()
+                    .and_then(Event::name)
+                    .is_none_or(|name| !end_activities.contains(&name))
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Directly-follows graph in GraphViz DOT, with a node per activity and an edge
+    /// labeled with its count for each pair occurring at least `min_frequency` times.
+    ///
+    /// Activity nodes have IDs `n0`, `n1` and so on in name order and are labeled with
+    /// their names.
+    pub fn dfg_to_dot(&self, min_frequency: usize) -> String {
+        self.dfg_to_dot_impl(min_frequency, false)
+    }
+
+    /// [`Log::dfg_to_dot`] with additional unlabeled start and end nodes, with IDs
+    /// `__start` and `__end`, connected to the activities traces start and end with.
+    pub fn dfg_to_dot_with_start_end(&self, min_frequency: usize) -> String {
+        self.dfg_to_dot_impl(min_frequency, true)
+    }
+
+    fn dfg_to_dot_impl(&self, min_frequency: usize, start_end: bool) -> String {
+        fn quote(name: &str) -> String {
+            format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+        }
+        let mut activities: Vec<&str> = self
+            .traces
+            .iter()
+            .flat_map(Trace::activities)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        activities.sort_unstable();
+        // Nodes are referred to by index rather than by name, so that no activity name
+        // can be the ID of another node such as `__start`.
+        let ids: HashMap<&str, usize> = activities
+            .iter()
+            .enumerate()
+            .map(|(index, activity)| (*activity, index))
+            .collect();
+        let mut dot = String::from("digraph dfg {\n");
+        if start_end {
+            dot.push_str("    __start [shape=circle, label=\"\"];\n");
+            dot.push_str("    __end [shape=doublecircle, label=\"\"];\n");
+        }
+        for (index, activity) in activities.iter().enumerate() {
+            writeln!(dot, "    n{index} [label={}];", quote(activity)).unwrap();
+        }
+        let mut follows: Vec<_> = self
+            .directly_follows()
+            .into_iter()
+            .filter(|&(_, count)| count >= min_frequency)
+            .map(|((from, to), count)| (ids[from.as_str()], ids[to.as_str()], count))
+            .collect();
+        follows.sort_unstable();
+        for (from, to, count) in follows {
+            writeln!(dot, "    n{from} -> n{to} [label=\"{count}\"];").unwrap();
+        }
+        if start_end {
+            let boundary = |counts: HashMap<String, usize>| {
+                let mut counts: Vec<_> = counts
+                    .into_iter()
+                    .filter(|&(_, count)| count >= min_frequency)
+                    .filter_map(|(activity, count)| Some((*ids.get(activity.as_str())?, count)))
+                    .collect();
+                counts.sort_unstable();
+                counts
+            };
+            for (to, count) in boundary(self.start_activities()) {
+                writeln!(dot, "    __start -> n{to} [label=\"{count}\"];").unwrap();
+            }
+            for (from, count) in boundary(self.end_activities()) {
+                writeln!(dot, "    n{from} -> __end [label=\"{count}\"];").unwrap();
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Give every trace and event of this log and its nested logs the attributes of
+    /// the globals of its scope that it lacks, such as those left out by
+    /// [`WriteOptions::omit_defaults`](crate::WriteOptions::omit_defaults).
+    pub fn resolve_globals(&mut self) {
+        fn fill(attributes: &mut HashMap<Key, Attribute>, globals: &HashMap<Key, Attribute>) {
+            for (key, value) in globals {
+                if !attributes.contains_key(key) {
+                    attributes.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        for trace in &mut self.traces {
+            fill(&mut trace.attributes, &self.trace_globals);
+            for event in &mut trace.events {
+                fill(&mut event.attributes, &self.event_globals);
+            }
+        }
+        for event in &mut self.events {
+            fill(&mut event.attributes, &self.event_globals);
+        }
+        for sub_log in &mut self.sub_logs {
+            sub_log.resolve_globals();
+        }
+    }
+}
+
+/// How [`Log::handover_of_work`] treats events without an `org:resource`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissingResource {
+    /// No handover is counted across the event.
+    BreakChain,
+    /// The event is ignored, connecting its neighbours.
+    Skip,
+}
+
+/// How [`Log::strip_timezones`] removes the offset of datetimes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimezoneOffset {
+    /// The time is converted to UTC before the offset is dropped.
+    ConvertToUtc,
+    /// The offset is dropped, keeping the local wall-clock time.
+    Truncate,
+}
+
+fn write_tree_element(
+    tree: &mut String,
+    label: &str,
+    attributes: &HashMap<Key, Attribute>,
+    depth: usize,
+) {
+    let indent = "  ".repeat(depth);
+    match attributes.get("concept:name") {
+        Some(name) => writeln!(tree, "{indent}{label} concept:name={name}").unwrap(),
+        None => writeln!(tree, "{indent}{label}").unwrap(),
+    }
+    write_tree_attributes(tree, attributes, Some("concept:name"), depth + 1);
+}
+
+fn write_tree_attributes(
+    tree: &mut String,
+    attributes: &HashMap<Key, Attribute>,
+    skip: Option<&str>,
+    depth: usize,
+) {
+    let indent = "  ".repeat(depth);
+    let mut attributes: Vec<_> = attributes.iter().collect();
+    attributes.sort_by(|a, b| a.0.cmp(b.0));
+    for (key, value) in attributes {
+        match value {
+            Attribute::List(list) => {
+                writeln!(tree, "{indent}{key}").unwrap();
+                write_tree_attributes(tree, list, None, depth + 1);
+            }
+            _ if skip == Some(&**key) => {}
+            _ => writeln!(tree, "{indent}{key}={value}").unwrap(),
+        }
+    }
+}
+
+/// Number of `events` with each `concept:name`.
+fn boundary_frequencies<'a>(events: impl Iterator<Item = &'a Event>) -> HashMap<String, usize> {
+    let mut frequencies = HashMap::new();
+    for name in events.filter_map(Event::name) {
+        *frequencies.entry(name.to_owned()).or_insert(0) += 1;
+    }
+    frequencies
 }
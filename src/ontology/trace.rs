@@ -1,9 +1,376 @@
+use crate::ontology::attribute;
+use crate::ontology::event;
 use crate::ontology::Attribute;
 use crate::ontology::Event;
+use crate::ontology::Key;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::FixedOffset;
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::collections::VecDeque;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Trace {
-    pub attributes: HashMap<String, Attribute>,
+    pub attributes: HashMap<Key, Attribute>,
     pub events: Vec<Event>,
 }
+
+impl Trace {
+    /// Value of the `concept:name` attribute.
+    pub fn name(&self) -> Option<&str> {
+        self.attributes.get("concept:name")?.as_str()
+    }
+
+    /// `concept:name` of each event in order, skipping events without one.
+    pub fn activities(&self) -> Vec<&str> {
+        self.events.iter().filter_map(Event::name).collect()
+    }
+
+    /// Move the events out of this trace.
+    pub fn into_events(self) -> Vec<Event> {
+        self.events
+    }
+
+    /// Seconds from the first timestamped event to each event, `None` for events
+    /// without a timestamp.
+    pub fn elapsed_times(&self) -> Vec<Option<f64>> {
+        let start = self.events.iter().find_map(Event::timestamp);
+        self.events
+            .iter()
+            .map(|event| Some((event.timestamp()? - start?).as_seconds_f64()))
+            .collect()
+    }
+
+    /// Copy the trace attribute `key` into every event that lacks it.
+    pub fn broadcast_attribute(&mut self, key: &str) {
+        let Some((key, value)) = self.attributes.get_key_value(key) else {
+            return;
+        };
+        for event in &mut self.events {
+            if !event.attributes.contains_key(key) {
+                event.attributes.insert(key.clone(), value.clone());
+            }
+        }
+    }
+
+    /// Attributes for each of `keys`, in the same order.
+    pub fn get_many(&self, keys: &[&str]) -> Vec<Option<&Attribute>> {
+        attribute::get_many(&self.attributes, keys)
+    }
+
+    /// Sum of the `cost:amount` of the events, `None` if no event has a cost.
+    pub fn total_cost(&self) -> Option<f64> {
+        event::total_cost(&self.events)
+    }
+
+    /// Keep only the first `max_len` events.
+    pub fn truncate_events(&mut self, max_len: usize) {
+        self.events.truncate(max_len);
+    }
+
+    /// Keep only the last `max_len` events.
+    pub fn keep_last_events(&mut self, max_len: usize) {
+        let excess = self.events.len().saturating_sub(max_len);
+        self.events.drain(..excess);
+    }
+
+    /// Activity sequence identifying the variant of this trace.
+    pub fn variant_key(&self) -> VariantKey {
+        VariantKey(self.activities().into_iter().map(String::from).collect())
+    }
+
+    /// Leaf attributes including the contents of nested lists, with `/`-separated key
+    /// paths such as `cost:drivers/cost:type`.
+    pub fn attributes_flat(&self) -> impl Iterator<Item = (String, &Attribute)> {
+        attribute::flatten(&self.attributes).into_iter()
+    }
+
+    /// Earliest event timestamp, regardless of document order.
+    pub fn start_time(&self) -> Option<DateTime<FixedOffset>> {
+        self.events.iter().filter_map(Event::timestamp).min()
+    }
+
+    /// Latest event timestamp, regardless of document order.
+    pub fn end_time(&self) -> Option<DateTime<FixedOffset>> {
+        self.events.iter().filter_map(Event::timestamp).max()
+    }
+
+    /// Time from [`Trace::start_time`] to [`Trace::end_time`], `None` with fewer than
+    /// two timestamped events.
+    pub fn duration(&self) -> Option<Duration> {
+        self.events.iter().filter_map(Event::timestamp).nth(1)?;
+        Some(self.end_time()? - self.start_time()?)
+    }
+
+    /// `concept:name` of the event following each event, `None` for the last event and
+    /// for followers without a name.
+    pub fn next_activities(&self) -> Vec<Option<&str>> {
+        self.events
+            .iter()
+            .skip(1)
+            .map(Event::name)
+            .chain(std::iter::once(None))
+            .take(self.events.len())
+            .collect()
+    }
+
+    /// First event of the trace.
+    pub fn first_event(&self) -> Option<&Event> {
+        self.events.first()
+    }
+
+    /// Last event of the trace.
+    pub fn last_event(&self) -> Option<&Event> {
+        self.events.last()
+    }
+
+    /// Copy of this trace with every event attribute replaced by `f` of its key and
+    /// value, dropping the attributes for which `f` returns `None`.
+    ///
+    /// Trace attributes are copied unchanged.
+    pub fn clone_with<F: Fn(&str, &Attribute) -> Option<Attribute>>(&self, f: F) -> Trace {
+        let events = self
+            .events
+            .iter()
+            .map(|event| {
+                Event::new(
+                    event
+                        .attributes
+                        .iter()
+                        .filter_map(|(key, value)| Some((key.clone(), f(key, value)?)))
+                        .collect(),
+                )
+            })
+            .collect();
+        Trace {
+            attributes: self.attributes.clone(),
+            events,
+        }
+    }
+
+    /// Activity name pairs whose intervals overlap in time, ordered by the start of the
+    /// earlier interval.
+    ///
+    /// Intervals run from a `start` event to the next `complete` event with the same
+    /// `concept:name`, in document order. Events without a name or timestamp and
+    /// unmatched events are ignored, as are intervals that only touch.
+    pub fn overlapping_activities(&self) -> Vec<(String, String)> {
+        let mut open: HashMap<&str, VecDeque<_>> = HashMap::new();
+        let mut intervals = Vec::new();
+        for event in &self.events {
+            let (Some(name), Some(timestamp)) = (event.name(), event.timestamp()) else {
+                continue;
+            };
+            match event.lifecycle_transition() {
+                Some("start") => open.entry(name).or_default().push_back(timestamp),
+                Some("complete") => {
+                    if let Some(start) = open.get_mut(name).and_then(VecDeque::pop_front) {
+                        intervals.push((start, timestamp, name));
+                    }
+                }
+                _ => {}
+            }
+        }
+        intervals.sort_by_key(|&(start, _, _)| start);
+        let mut overlaps = Vec::new();
+        for (i, &(start, complete, name)) in intervals.iter().enumerate() {
+            for &(other_start, other_complete, other_name) in &intervals[i + 1..] {
+                if other_start < complete && start < other_complete {
+                    overlaps.push((name.to_owned(), other_name.to_owned()));
+                }
+            }
+        }
+        overlaps
+    }
+
+    /// Stably sort the events by their value of attribute `key`, as compared by
+    /// [`Attribute::partial_cmp_value`].
+    ///
+    /// Events lacking the attribute come last. Values of different kinds are ordered
+    /// numbers, datetimes, strings, ids, booleans, then values that cannot be compared
+    /// such as lists.
+    pub fn sort_events_by_attribute(&mut self, key: &str) {
+        fn rank(value: Option<&Attribute>) -> u8 {
+            match value {
+                Some(Attribute::Long(_)) => 0,
+                Some(Attribute::Double(value)) if !value.is_nan() => 0,
+                Some(value @ Attribute::DateTime(_)) if value.as_datetime().is_some() => 1,
+                Some(Attribute::String(_)) => 2,
+                Some(Attribute::ID(_)) => 3,
+                Some(Attribute::Boolean(_)) => 4,
+                Some(_) => 5,
+                None => 6,
+            }
+        }
+        self.events.sort_by(|a, b| {
+            let (a, b) = (a.attributes.get(key), b.attributes.get(key));
+            rank(a).cmp(&rank(b)).then_with(|| match (a, b) {
+                (Some(a), Some(b)) => a.partial_cmp_value(b).unwrap_or(Ordering::Equal),
+                _ => Ordering::Equal,
+            })
+        });
+    }
+
+    /// Lifecycle of every activity instance, in order of its first event.
+    ///
+    /// Events are paired by `concept:name` and `concept:instance`: `schedule` and
+    /// `assign` open an instance, `start` starts the oldest unstarted one and `complete`
+    /// closes the oldest open one, opening instances as needed. Events with another or
+    /// no `lifecycle:transition` are reported as incomplete instances of their own.
+    /// Events without a `concept:name` are ignored.
+    pub fn activity_timings(&self) -> Vec<ActivityTiming> {
+        let mut timings: Vec<ActivityTiming> = Vec::new();
+        // Indices into `timings` of the instances not yet completed, by activity.
+        let mut open: HashMap<(&str, Option<&str>), Vec<usize>> = HashMap::new();
+        for event in &self.events {
+            let Some(activity) = event.name() else {
+                continue;
+            };
+            let instance = event
+                .attributes
+                .get("concept:instance")
+                .and_then(Attribute::as_str);
+            let timestamp = event.timestamp();
+            let new = || ActivityTiming {
+                activity: activity.to_owned(),
+                instance: instance.map(String::from),
+                scheduled: None,
+                started: None,
+                completed: None,
+            };
+            let open = open.entry((activity, instance)).or_default();
+            match event.lifecycle_transition() {
+                Some("schedule") => {
+                    let index = find_or_open(
+                        &mut timings,
+                        open,
+                        |t| t.started.is_none() && t.scheduled.is_none(),
+                        new,
+                    );
+                    timings[index].scheduled = timestamp;
+                }
+                Some("assign") => {
+                    let index = find_or_open(&mut timings, open, |t| t.started.is_none(), new);
+                    let scheduled = &mut timings[index].scheduled;
+                    if scheduled.is_none() {
+                        *scheduled = timestamp;
+                    }
+                }
+                Some("start") => {
+                    let index = find_or_open(&mut timings, open, |t| t.started.is_none(), new);
+                    timings[index].started = timestamp;
+                }
+                Some("complete") => {
+                    let index = find_or_open(&mut timings, open, |_| true, new);
+                    open.retain(|&i| i != index);
+                    timings[index].completed = timestamp;
+                }
+                _ => timings.push(new()),
+            }
+        }
+        timings
+    }
+
+    /// Attributes in key order, for deterministic iteration.
+    pub fn attributes_sorted(&self) -> Vec<(&Key, &Attribute)> {
+        attribute::sorted(&self.attributes)
+    }
+
+    /// Replace each `start` event and the next `complete` event with the same
+    /// `concept:name` by the complete event alone, annotated with a `duration` attribute
+    /// holding the seconds between them.
+    ///
+    /// `duration` is only added when both events are timestamped. Unmatched events are
+    /// kept as they are.
+    pub fn collapse_lifecycle(&mut self) {
+        let mut open: HashMap<String, VecDeque<usize>> = HashMap::new();
+        let mut matched_starts = Vec::new();
+        let mut durations = Vec::new();
+        for (index, event) in self.events.iter().enumerate() {
+            let Some(name) = event.name() else {
+                continue;
+            };
+            match event.lifecycle_transition() {
+                Some("start") => open.entry(name.to_owned()).or_default().push_back(index),
+                Some("complete") => {
+                    if let Some(start) = open.get_mut(name).and_then(VecDeque::pop_front) {
+                        matched_starts.push(start);
+                        let duration = self.events[start]
+                            .timestamp()
+                            .zip(event.timestamp())
+                            .map(|(start, complete)| complete - start);
+                        durations.push((index, duration));
+                    }
+                }
+                _ => {}
+            }
+        }
+        for (index, duration) in durations {
+            if let Some(duration) = duration {
+                let seconds = Attribute::Double(duration.as_seconds_f64());
+                self.events[index]
+                    .attributes
+                    .insert(Key::from("duration"), seconds);
+            }
+        }
+        matched_starts.sort_unstable();
+        let mut matched_starts = matched_starts.into_iter().peekable();
+        let mut index = 0;
+        self.events.retain(|_| {
+            let keep = matched_starts.next_if_eq(&index).is_none();
+            index += 1;
+            keep
+        });
+    }
+}
+
+/// Activity sequence of a trace, usable as a map key for grouping traces by variant.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct VariantKey(pub Vec<String>);
+
+/// Index of the oldest `open` instance matching `unfilled`, or of a new instance from
+/// `new` added to `timings` and `open`.
+fn find_or_open(
+    timings: &mut Vec<ActivityTiming>,
+    open: &mut Vec<usize>,
+    unfilled: impl Fn(&ActivityTiming) -> bool,
+    new: impl FnOnce() -> ActivityTiming,
+) -> usize {
+    if let Some(&index) = open.iter().find(|&&i| unfilled(&timings[i])) {
+        return index;
+    }
+    timings.push(new());
+    open.push(timings.len() - 1);
+    timings.len() - 1
+}
+
+/// Lifecycle of one activity instance, see [`Trace::activity_timings`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActivityTiming {
+    pub activity: String,
+    /// Value of `concept:instance`, distinguishing instances of the same activity.
+    pub instance: Option<String>,
+    /// Timestamp of the `schedule` or, failing that, the `assign` event.
+    pub scheduled: Option<DateTime<FixedOffset>>,
+    pub started: Option<DateTime<FixedOffset>>,
+    pub completed: Option<DateTime<FixedOffset>>,
+}
+
+impl ActivityTiming {
+    /// Time from being scheduled to being started.
+    pub fn waiting_time(&self) -> Option<Duration> {
+        Some(self.started? - self.scheduled?)
+    }
+
+    /// Time from being started to being completed.
+    pub fn service_time(&self) -> Option<Duration> {
+        Some(self.completed? - self.started?)
+    }
+
+    /// Whether both the start and the completion of the instance are known.
+    pub fn is_complete(&self) -> bool {
+        self.started.is_some() && self.completed.is_some()
+    }
+}
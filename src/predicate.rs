@@ -0,0 +1,70 @@
+use crate::Attribute;
+use crate::Event;
+use crate::Log;
+use std::cmp::Ordering;
+
+/// Comparison applied by a [`Predicate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    /// String attribute contains the string value.
+    Contains,
+}
+
+/// Condition on one event attribute, compared with [`Attribute::partial_cmp_value`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Predicate {
+    pub key: String,
+    pub operator: Operator,
+    pub value: Attribute,
+}
+
+impl Predicate {
+    pub fn new(key: impl Into<String>, operator: Operator, value: Attribute) -> Self {
+        Self {
+            key: key.into(),
+            operator,
+            value,
+        }
+    }
+
+    /// Whether `event` has the attribute and it satisfies the condition.
+    ///
+    /// `Ne` holds for values that are not comparable with the predicate value, the
+    /// other comparisons do not.
+    pub fn matches(&self, event: &Event) -> bool {
+        let Some(attribute) = event.attributes.get(self.key.as_str()) else {
+            return false;
+        };
+        let ordering = attribute.partial_cmp_value(&self.value);
+        match self.operator {
+            Operator::Eq => ordering == Some(Ordering::Equal),
+            Operator::Ne => ordering != Some(Ordering::Equal),
+            Operator::Lt => ordering == Some(Ordering::Less),
+            Operator::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+            Operator::Gt => ordering == Some(Ordering::Greater),
+            Operator::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+            Operator::Contains => match (attribute, &self.value) {
+                (Attribute::String(haystack), Attribute::String(needle)) => {
+                    haystack.contains(needle.as_str())
+                }
+                _ => false,
+            },
+        }
+    }
+}
+
+impl Log {
+    /// Remove every event, in traces and directly under the log, not matching `predicate`.
+    pub fn filter_events(&mut self, predicate: &Predicate) {
+        for trace in &mut self.traces {
+            trace.events.retain(|event| predicate.matches(event));
+        }
+        self.events.retain(|event| predicate.matches(event));
+    }
+}
@@ -0,0 +1,573 @@
+use crate::intern::Interner;
+use crate::ontology::attribute::parse_datetime;
+use crate::Attribute;
+use crate::Classifier;
+use crate::Event;
+use crate::Extension;
+use crate::Key;
+use crate::Log;
+use crate::Trace;
+use crate::XesError;
+use chrono::SecondsFormat;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::num::IntErrorKind;
+use std::num::ParseIntError;
+use std::ops::Range;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Options controlling how `XES` is read.
+#[derive(Debug, Clone, Default)]
+pub struct ReadOptions {
+    /// Reject input that is readable but does not conform to the standard.
+    ///
+    /// Otherwise unknown versions are accepted, repeated identical extension declarations
+    /// are collapsed to the first one and of attributes with the same key the last one is
+    /// kept. Declarations of the same uri under different prefixes are always kept.
+    pub strict: bool,
+    /// Remove leading and trailing whitespace from string values, which are otherwise
+    /// kept verbatim.
+    pub trim_strings: bool,
+    /// Lowercase attribute keys, so `Concept:Name` and `concept:name` become the same key.
+    ///
+    /// This is lossy: the original casing is not kept and attributes whose keys differ
+    /// only by case replace each other.
+    pub lowercase_keys: bool,
+    /// Drop traces and events that fail to parse instead of failing the whole read.
+    ///
+    /// The errors of dropped elements are returned by [`read_str_with_diagnostics`].
+    pub skip_errors: bool,
+    /// Keep namespace declarations such as `xmlns:xsi` and other attributes of `<log>`,
+    /// such as `xsi:schemaLocation`, in [`Log::xml_attributes`].
+    pub keep_log_xml_attributes: bool,
+    /// Find `<log>` elements anywhere in the document, such as inside a wrapper
+    /// document, instead of only at its root.
+    ///
+    /// Logs nested inside other logs are still read as [`Log::sub_logs`].
+    pub deep_search: bool,
+    /// Store values in their normalized form, so that writing them back is
+    /// deterministic: booleans are accepted in any case, such as `TRUE`, and datetimes
+    /// are rewritten in RFC 3339, such as `2020-01-01T12:00:00+00:00`.
+    ///
+    /// Numbers are always stored parsed, so `1.50` is written back as `1.5` either way.
+    pub canonicalize_values: bool,
+    /// Give traces and events the global attributes they lack, as by
+    /// [`Log::resolve_globals`].
+    pub resolve_globals: bool,
+}
+
+/// State shared by the parse functions during a single read.
+struct Context<'a> {
+    options: &'a ReadOptions,
+    interner: Interner,
+    diagnostics: Vec<XesError>,
+    /// Number of enclosing lists and logs of the element being parsed.
+    depth: usize,
+}
+
+/// Deepest nesting of lists and logs that is read, so that hostile input cannot
+/// exhaust the stack.
+pub(crate) const MAX_NESTING_DEPTH: usize = 256;
+
+/// Result of `f`, which parses the contents of an element one nesting level deeper,
+/// rejecting input nested more than [`MAX_NESTING_DEPTH`] levels.
+fn nested<T>(
+    cx: &mut Context,
+    f: impl FnOnce(&mut Context) -> Result<T, XesError>,
+) -> Result<T, XesError> {
+    if cx.depth == MAX_NESTING_DEPTH {
+        return Err(XesError::NestingTooDeep(MAX_NESTING_DEPTH));
+    }
+    cx.depth += 1;
+    let result = f(cx);
+    cx.depth -= 1;
+    result
+}
+
+/// Element parse result, with the error recorded and the element dropped if
+/// [`ReadOptions::skip_errors`] is set.
+fn recover<T>(result: Result<T, XesError>, cx: &mut Context) -> Result<Option<T>, XesError> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(error) if cx.options.skip_errors => {
+            cx.diagnostics.push(error);
+            Ok(None)
+        }
+        Err(error) => Err(error),
+    }
+}
+
+pub(crate) const ATTRIBUTE_TAGS: [&str; 11] = [
+    "list",
+    "container",
+    "string",
+    "date",
+    "datetime",
+    "long",
+    "int",
+    "double",
+    "float",
+    "boolean",
+    "id",
+];
+
+fn required<'a>(
+    node: &roxmltree::Node<'a, '_>,
+    attribute: &'static str,
+) -> Result<&'a str, XesError> {
+    node.attribute(attribute)
+        .ok_or_else(|| XesError::MissingAttribute {
+            element: node.tag_name().name().to_owned(),
+            attribute,
+        })
+}
+
+/// Add attribute `key` to `attributes`, rejecting an existing key if
+/// [`ReadOptions::strict`] is set.
+fn insert_attribute(
+    attributes: &mut HashMap<Key, Attribute>,
+    (key, value): (Key, Attribute),
+    cx: &Context,
+) -> Result<(), XesError> {
+    if cx.options.strict && attributes.contains_key(&key) {
+        return Err(XesError::DuplicateKey(key.to_string()));
+    }
+    attributes.insert(key, value);
+    Ok(())
+}
+
+fn parse_attributes(
+    parente: &roxmltree::Node,
+    cx: &mut Context,
+) -> Result<HashMap<Key, Attribute>, XesError> {
+    let mut attributes = HashMap::new();
+    for attributee in parente
+        .children()
+        .filter(|e| ATTRIBUTE_TAGS.contains(&e.tag_name().name()))
+    {
+        insert_attribute(&mut attributes, parse_attribute(&attributee, cx)?, cx)?;
+    }
+    Ok(attributes)
+}
+
+/// Text content of a `<string>` element that carries its value as text instead of
+/// as a `value` attribute.
+///
+/// CDATA sections are read verbatim and text split by comments is joined.
+fn text_value<'a>(attributee: &roxmltree::Node<'a, '_>) -> Option<Cow<'a, str>> {
+    if attributee.tag_name().name() != "string" || attributee.children().any(|e| e.is_element()) {
+        return None;
+    }
+    let mut texts = attributee
+        .children()
+        .filter(|e| e.is_text())
+        .filter_map(|e| e.text());
+    let first = texts.next()?;
+    match texts.next() {
+        None => Some(Cow::Borrowed(first)),
+        Some(second) => Some(Cow::Owned(
+            [first, second].into_iter().chain(texts).collect(),
+        )),
+    }
+}
+
+fn parse_long(key: &str, value: &str) -> Result<i64, XesError> {
+    value
+        .parse()
+        .map_err(|error: ParseIntError| match error.kind() {
+            IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => XesError::LongOutOfRange {
+                key: key.to_owned(),
+                value: value.to_owned(),
+            },
+            _ => XesError::InvalidValue {
+                key: key.to_owned(),
+                value: value.to_owned(),
+            },
+        })
+}
+
+/// Scalar attribute of element `tag` holding `value`.
+pub(crate) fn parse_scalar(
+    tag: &str,
+    key: &str,
+    value: &str,
+    options: &ReadOptions,
+) -> Result<Attribute, XesError> {
+    let invalid = || XesError::InvalidValue {
+        key: key.to_owned(),
+        value: value.to_owned(),
+    };
+    Ok(match tag {
+        "string" if options.trim_strings => Attribute::String(value.trim().to_owned()),
+        "string" => Attribute::String(value.to_owned()),
+        "date" | "datetime" if options.canonicalize_values => Attribute::DateTime(
+            parse_datetime(value)
+                .map(|datetime| datetime.to_rfc3339_opts(SecondsFormat::AutoSi, false))
+                .unwrap_or_else(|| value.to_owned()),
+        ),
+        "date" | "datetime" => Attribute::DateTime(value.to_owned()),
+        "long" | "int" => Attribute::Long(parse_long(key, value)?),
+        "double" | "float" => Attribute::Double(value.parse().map_err(|_| invalid())?),
+        "boolean" if options.canonicalize_values => {
+            Attribute::Boolean(value.to_ascii_lowercase().parse().map_err(|_| invalid())?)
+        }
+        "boolean" => Attribute::Boolean(value.parse().map_err(|_| invalid())?),
+        _ => Attribute::ID(value.to_owned()),
+    })
+}
+
+/// Value of a scalar element `tag` with neither a value nor nested attributes, such as
+/// an empty string for `<string key="x"/>`.
+pub(crate) fn default_scalar(tag: &str) -> Attribute {
+    match tag {
+        "date" | "datetime" => Attribute::DateTime(String::new()),
+        "long" | "int" => Attribute::Long(0),
+        "double" | "float" => Attribute::Double(0.0),
+        "boolean" => Attribute::Boolean(false),
+        "id" => Attribute::ID(String::new()),
+        _ => Attribute::String(String::new()),
+    }
+}
+
+fn parse_attribute(
+    attributee: &roxmltree::Node,
+    cx: &mut Context,
+) -> Result<(Key, Attribute), XesError> {
+    let key = required(attributee, "key")?;
+    let tag = attributee.tag_name().name();
+    let value = attributee
+        .attribute("value")
+        .map(Cow::Borrowed)
+        .or_else(|| text_value(attributee));
+    let value = match value {
+        Some(value) if !matches!(tag, "list" | "container") => {
+            parse_scalar(tag, key, &value, cx.options)?
+        }
+        None if !matches!(tag, "list" | "container")
+            && !attributee
+                .children()
+                .any(|e| ATTRIBUTE_TAGS.contains(&e.tag_name().name())) =>
+        {
+            default_scalar(tag)
+        }
+        _ => Attribute::List(nested(cx, |cx| parse_attributes(attributee, cx))?),
+    };
+    let key = if cx.options.lowercase_keys {
+        cx.interner.intern(&key.to_lowercase())
+    } else {
+        cx.interner.intern(key)
+    };
+    Ok((key, value))
+}
+
+fn parse_event(evente: &roxmltree::Node, cx: &mut Context) -> Result<Event, XesError> {
+    let attributes = parse_attributes(evente, cx)?;
+    Ok(Event::new(attributes))
+}
+
+fn parse_trace(tracee: &roxmltree::Node, cx: &mut Context) -> Result<Trace, XesError> {
+    let mut attributes = HashMap::new();
+    let mut events = Vec::new();
+    for childe in tracee.children() {
+        match childe.tag_name().name() {
+            "event" => {
+                if let Some(event) = recover(parse_event(&childe, cx), cx)? {
+                    events.push(event);
+                }
+            }
+            tag if ATTRIBUTE_TAGS.contains(&tag) => {
+                insert_attribute(&mut attributes, parse_attribute(&childe, cx)?, cx)?;
+            }
+            _ => {}
+        }
+    }
+    Ok(Trace { attributes, events })
+}
+
+/// Whether `version` has the `major.minor` shape of published `XES` versions.
+fn is_known_version(version: &str) -> bool {
+    match version.split_once('.') {
+        Some((major, minor)) => {
+            let numeric = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+            numeric(major) && numeric(minor)
+        }
+        None => false,
+    }
+}
+
+/// Namespace declarations and attributes of `<log>` other than `version` and `features`.
+fn log_xml_attributes(loge: &roxmltree::Node) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+    for namespace in loge.namespaces() {
+        let name = match namespace.name() {
+            Some(prefix) => format!("xmlns:{prefix}"),
+            None => "xmlns".to_owned(),
+        };
+        attributes.push((name, namespace.uri().to_owned()));
+    }
+    for attribute in loge.attributes() {
+        let name = match attribute.namespace() {
+            Some(uri) => match loge.lookup_prefix(uri) {
+                Some(prefix) => format!("{prefix}:{}", attribute.name()),
+                None => attribute.name().to_owned(),
+            },
+            None if matches!(attribute.name(), "version" | "features") => continue,
+            None => attribute.name().to_owned(),
+        };
+        attributes.push((name, attribute.value().to_owned()));
+    }
+    attributes
+}
+
+/// Entries of a comma-separated `features` attribute.
+pub(crate) fn parse_features(features: &str) -> Vec<String> {
+    features
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn parse_log(loge: &roxmltree::Node, cx: &mut Context) -> Result<Log, XesError> {
+    let version = required(loge, "version")?;
+    if cx.options.strict && !is_known_version(version) {
+        return Err(XesError::UnsupportedVersion(version.to_owned()));
+    }
+    let version = version.to_owned();
+    let features = parse_features(loge.attribute("features").unwrap_or_default());
+    let mut log = Log::new(version, features);
+    if cx.options.keep_log_xml_attributes {
+        log.xml_attributes = log_xml_attributes(loge);
+    }
+    for childe in loge.children() {
+        match childe.tag_name().name() {
+            "extension" => {
+                let name = required(&childe, "name")?.to_owned();
+                let prefix = required(&childe, "prefix")?.to_owned();
+                let uri = required(&childe, "uri")?.to_owned();
+                let extension = Extension { name, prefix, uri };
+                if log.extensions.contains(&extension) {
+                    if cx.options.strict {
+                        return Err(XesError::DuplicateExtension(extension.uri));
+                    }
+                    continue;
+                }
+                log.extensions.push(extension);
+            }
+            "classifier" => log.classifiers.push(Classifier {
+                name: required(&childe, "name")?.to_owned(),
+                scope: childe.attribute("scope").map(str::to_owned),
+                keys: Classifier::parse_keys(required(&childe, "keys")?),
+            }),
+            "global" => {
+                let attributes = parse_attributes(&childe, cx)?;
+                match childe.attribute("scope") {
+                    Some("trace") => log.trace_globals.extend(attributes),
+                    _ => log.event_globals.extend(attributes),
+                }
+            }
+            "trace" => {
+                if let Some(trace) = recover(parse_trace(&childe, cx), cx)? {
+                    log.traces.push(trace);
+                }
+            }
+            "event" => {
+                if let Some(event) = recover(parse_event(&childe, cx), cx)? {
+                    log.events.push(event);
+                }
+            }
+            "log" => log.sub_logs.push(nested(cx, |cx| parse_log(&childe, cx))?),
+            tag if ATTRIBUTE_TAGS.contains(&tag) => {
+                insert_attribute(&mut log.attributes, parse_attribute(&childe, cx)?, cx)?;
+            }
+            _ => {}
+        }
+    }
+    Ok(log)
+}
+
+/// Append the `<log>` elements under `node` that are not inside another log to `loges`.
+fn find_logs<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    loges: &mut Vec<roxmltree::Node<'a, 'input>>,
+) {
+    // Depth first with an explicit stack, as wrapper documents may be nested arbitrarily deep.
+    let mut stack: Vec<_> = node.children().filter(|e| e.is_element()).collect();
+    stack.reverse();
+    while let Some(childe) = stack.pop() {
+        if childe.tag_name().name() == "log" {
+            loges.push(childe);
+        } else {
+            let start = stack.len();
+            stack.extend(childe.children().filter(|e| e.is_element()));
+            stack[start..].reverse();
+        }
+    }
+}
+
+/// Transform `XES`-text to Rust representation.
+///
+/// Input that is empty or contains no `<log>` element is rejected with
+/// [`XesError::NoLogs`]. Whitespace around the root element, such as trailing blank
+/// lines, is ignored.
+pub fn read_str(text: &str) -> Result<Vec<Log>, XesError> {
+    read_str_with_options(text, &ReadOptions::default())
+}
+
+/// Transform `XES`-text to Rust representation using `options`.
+pub fn read_str_with_options(text: &str, options: &ReadOptions) -> Result<Vec<Log>, XesError> {
+    read_str_with_diagnostics(text, options).map(|(logs, _)| logs)
+}
+
+/// Transform `XES`-text to Rust representation using `options`, also returning the
+/// errors of elements dropped because of [`ReadOptions::skip_errors`].
+pub fn read_str_with_diagnostics(
+    text: &str,
+    options: &ReadOptions,
+) -> Result<(Vec<Log>, Vec<XesError>), XesError> {
+    let (logs, diagnostics) = read_str_positioned(text, options)?;
+    Ok((logs.into_iter().map(|(_, log)| log).collect(), diagnostics))
+}
+
+/// Transform `XES`-text to Rust representation using `options`, also returning the
+/// byte range of `text` each log was read from, from `<log` to `</log>`.
+pub fn read_str_with_ranges(
+    text: &str,
+    options: &ReadOptions,
+) -> Result<Vec<(Range<usize>, Log)>, XesError> {
+    let (logs, _) = read_str_positioned(text, options)?;
+    Ok(logs
+        .into_iter()
+        .map(|(start, log)| (start..start + element_length(&text[start..]), log))
+        .collect())
+}
+
+/// Length of the element that `text` starts with, up to and including its end tag.
+fn element_length(text: &str) -> usize {
+    let mut reader = quick_xml::Reader::from_str(text);
+    let mut depth = 0usize;
+    loop {
+        match reader.read_event() {
+            Ok(quick_xml::events::Event::Start(_)) => depth += 1,
+            Ok(quick_xml::events::Event::End(_)) => depth -= 1,
+            Ok(quick_xml::events::Event::Empty(_)) => {}
+            Ok(quick_xml::events::Event::Eof) | Err(_) => return text.len(),
+            Ok(_) => continue,
+        }
+        if depth == 0 {
+            return reader.buffer_position();
+        }
+    }
+}
+
+/// Logs of `text` with the byte offset of their `<log` start tag, and diagnostics.
+#[allow(clippy::type_complexity)]
+fn read_str_positioned(
+    text: &str,
+    options: &ReadOptions,
+) -> Result<(Vec<(usize, Log)>, Vec<XesError>), XesError> {
+    if text.trim().is_empty() {
+        return Err(XesError::NoLogs);
+    }
+    let document = roxmltree::Document::parse(text)?;
+    let mut cx = Context {
+        options,
+        interner: Interner::default(),
+        diagnostics: Vec::new(),
+        depth: 0,
+    };
+    let mut logs = Vec::new();
+    let mut loges = Vec::new();
+    if options.deep_search {
+        find_logs(document.root(), &mut loges);
+    } else {
+        loges.extend(
+            document
+                .root()
+                .children()
+                .filter(|e| e.tag_name().name() == "log"),
+        );
+    }
+    for loge in loges {
+        let mut log = parse_log(&loge, &mut cx)?;
+        if options.resolve_globals {
+            log.resolve_globals();
+        }
+        logs.push((loge.position(), log));
+    }
+    if logs.is_empty() {
+        return Err(XesError::NoLogs);
+    }
+    Ok((logs, cx.diagnostics))
+}
+
+/// Transform `XES`-file to Rust representation.
+pub fn read<P: AsRef<Path>>(path: P) -> Result<Vec<Log>, XesError> {
+    read_with_options(path, &ReadOptions::default())
+}
+
+/// Transform `XES`-file to Rust representation using `options`.
+pub fn read_with_options<P: AsRef<Path>>(
+    path: P,
+    options: &ReadOptions,
+) -> Result<Vec<Log>, XesError> {
+    let text = std::fs::read_to_string(path)?;
+    read_str_with_options(&text, options)
+}
+
+/// Transform `XES`-file holding exactly one log to Rust representation.
+///
+/// Logs are searched for as with [`ReadOptions::deep_search`], so a wrapper document
+/// holding a single log is accepted. A file without logs is rejected with
+/// [`XesError::NoLogs`] and a file with more than one with [`XesError::MultipleLogs`].
+pub fn read_single<P: AsRef<Path>>(path: P) -> Result<Log, XesError> {
+    let options = ReadOptions {
+        deep_search: true,
+        ..ReadOptions::default()
+    };
+    let mut logs = read_with_options(path, &options)?;
+    match logs.len() {
+        1 => Ok(logs.remove(0)),
+        count => Err(XesError::MultipleLogs(count)),
+    }
+}
+
+/// Transform `XES`-file to Rust representation without blocking the async runtime.
+///
+/// The file is read with `tokio::fs` and parsed on a blocking task.
+#[cfg(feature = "tokio")]
+pub async fn read_async<P: AsRef<Path>>(path: P) -> Result<Vec<Log>, XesError> {
+    let text = tokio::fs::read_to_string(path).await?;
+    match tokio::task::spawn_blocking(move || read_str(&text)).await {
+        Ok(result) => result,
+        Err(error) if error.is_panic() => std::panic::resume_unwind(error.into_panic()),
+        Err(error) => Err(std::io::Error::other(error).into()),
+    }
+}
+
+/// Transform every `.xes`-file in directory `path` to Rust representation.
+///
+/// Files are read in path order and a file that fails to read does not stop the
+/// others, its error is returned in its place.
+#[allow(clippy::type_complexity)]
+pub fn read_dir<P: AsRef<Path>>(
+    path: P,
+) -> Result<Vec<(PathBuf, Result<Vec<Log>, XesError>)>, XesError> {
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(path)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|extension| extension == "xes") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths
+        .into_iter()
+        .map(|path| {
+            let logs = read(&path);
+            (path, logs)
+        })
+        .collect())
+}
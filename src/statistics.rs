@@ -0,0 +1,113 @@
+use crate::Log;
+use chrono::Duration;
+use ordered_float::OrderedFloat;
+use std::collections::BTreeMap;
+
+/// Summary of a collection of numbers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumericSummary {
+    pub count: usize,
+    pub sum: f64,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+}
+
+impl NumericSummary {
+    /// Summarize `values`, `None` if there are none.
+    pub fn from_values(values: impl IntoIterator<Item = f64>) -> Option<Self> {
+        let mut values = values.into_iter();
+        let first = values.next()?;
+        let mut summary = Self {
+            count: 1,
+            sum: first,
+            min: first,
+            max: first,
+            mean: first,
+        };
+        for value in values {
+            summary.count += 1;
+            summary.sum += value;
+            summary.min = summary.min.min(value);
+            summary.max = summary.max.max(value);
+        }
+        summary.mean = summary.sum / summary.count as f64;
+        Some(summary)
+    }
+}
+
+/// Distribution of the number of events per trace.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LengthStats {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+    pub median: f64,
+    /// Population standard deviation.
+    pub std_dev: f64,
+}
+
+impl Log {
+    /// Distribution of the number of events per trace, `None` for a log without traces.
+    pub fn trace_length_stats(&self) -> Option<LengthStats> {
+        let mut lengths: Vec<i64> = self
+            .traces
+            .iter()
+            .map(|trace| trace.events.len() as i64)
+            .collect();
+        lengths.sort_unstable();
+        let (&min, &max) = (lengths.first()?, lengths.last()?);
+        let count = lengths.len() as f64;
+        let mean = lengths.iter().sum::<i64>() as f64 / count;
+        let variance = lengths
+            .iter()
+            .map(|&length| (length as f64 - mean).powi(2))
+            .sum::<f64>()
+            / count;
+        Some(LengthStats {
+            min: min as usize,
+            max: max as usize,
+            mean,
+            median: percentile(&lengths, 50.0),
+            std_dev: variance.sqrt(),
+        })
+    }
+
+    /// Summary of the `Long` and `Double` values of attribute `key` over all events.
+    pub fn numeric_summary(&self, key: &str) -> Option<NumericSummary> {
+        NumericSummary::from_values(
+            self.all_events()
+                .filter_map(|event| event.attributes.get(key)?.as_f64()),
+        )
+    }
+
+    /// Trace durations at each of the percentiles `ps`, given from 0 to 100, such as 50
+    /// for the median. Traces with fewer than two timestamps are excluded.
+    pub fn cycle_time_percentiles(&self, ps: &[f64]) -> BTreeMap<OrderedFloat<f64>, Duration> {
+        let mut durations: Vec<i64> = self
+            .traces
+            .iter()
+            .filter_map(|trace| trace.duration()?.num_nanoseconds())
+            .collect();
+        let mut percentiles = BTreeMap::new();
+        if durations.is_empty() {
+            return percentiles;
+        }
+        durations.sort_unstable();
+        for &p in ps {
+            let nanoseconds = percentile(&durations, p).round() as i64;
+            percentiles.insert(OrderedFloat(p), Duration::nanoseconds(nanoseconds));
+        }
+        percentiles
+    }
+}
+
+/// Value at percentile `p` (0 to 100) of sorted `values`, interpolating linearly
+/// between the closest ranks.
+fn percentile(sorted: &[i64], p: f64) -> f64 {
+    let rank = p.clamp(0.0, 100.0) / 100.0 * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    let weight = rank - lower as f64;
+    sorted[lower] as f64 * (1.0 - weight) + sorted[upper] as f64 * weight
+}
@@ -0,0 +1,435 @@
+use crate::intern::Interner;
+use crate::reader::default_scalar;
+use crate::reader::parse_features;
+use crate::reader::parse_scalar;
+use crate::reader::read_str_with_options;
+use crate::reader::ReadOptions;
+use crate::reader::ATTRIBUTE_TAGS;
+use crate::reader::MAX_NESTING_DEPTH;
+use crate::writer::write_event;
+use crate::writer::WriteOptions;
+use crate::Attribute;
+use crate::Event;
+use crate::Extension;
+use crate::Key;
+use crate::Log;
+use crate::Trace;
+use crate::XesError;
+use quick_xml::events::BytesStart;
+use quick_xml::events::Event as XmlEvent;
+use quick_xml::Reader;
+use quick_xml::Writer;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::path::Path;
+
+/// Attributes of a `<log>` element and the extensions it declares.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogHeader {
+    pub version: String,
+    pub features: Vec<String>,
+    pub extensions: Vec<Extension>,
+}
+
+/// Value of attribute `name` of element `start`.
+pub(crate) fn xml_attribute(start: &BytesStart, name: &str) -> Result<Option<String>, XesError> {
+    match start.try_get_attribute(name)? {
+        Some(attribute) => Ok(Some(attribute.unescape_value()?.into_owned())),
+        None => Ok(None),
+    }
+}
+
+/// Value of attribute `name` of element `start`, which must be present.
+pub(crate) fn required_xml_attribute(
+    start: &BytesStart,
+    name: &'static str,
+) -> Result<String, XesError> {
+    xml_attribute(start, name)?.ok_or_else(|| XesError::MissingAttribute {
+        element: String::from_utf8_lossy(start.local_name().as_ref()).into_owned(),
+        attribute: name,
+    })
+}
+
+fn parse_extension(start: &BytesStart) -> Result<Extension, XesError> {
+    Ok(Extension {
+        name: required_xml_attribute(start, "name")?,
+        prefix: required_xml_attribute(start, "prefix")?,
+        uri: required_xml_attribute(start, "uri")?,
+    })
+}
+
+/// Read the header of the first log in `reader`, stopping at its first trace or event.
+pub(crate) fn read_header<R: BufRead>(reader: &mut Reader<R>) -> Result<LogHeader, XesError> {
+    read_header_until_body(reader).map(|(header, _)| header)
+}
+
+/// Read the header of the first log in `reader`, also returning the trace or event
+/// element that ended it, if any.
+fn read_header_until_body<R: BufRead>(
+    reader: &mut Reader<R>,
+) -> Result<(LogHeader, Option<XmlEvent<'static>>), XesError> {
+    let mut buffer = Vec::new();
+    let mut header: Option<LogHeader> = None;
+    let mut body = None;
+    loop {
+        let event = reader.read_event_into(&mut buffer)?;
+        match &event {
+            XmlEvent::Start(start) | XmlEvent::Empty(start) => {
+                match (start.local_name().as_ref(), header.as_mut()) {
+                    (b"log", None) => {
+                        header = Some(LogHeader {
+                            version: required_xml_attribute(start, "version")?,
+                            features: parse_features(
+                                &xml_attribute(start, "features")?.unwrap_or_default(),
+                            ),
+                            extensions: Vec::new(),
+                        });
+                    }
+                    (b"extension", Some(header)) => {
+                        let extension = parse_extension(start)?;
+                        if !header.extensions.contains(&extension) {
+                            header.extensions.push(extension);
+                        }
+                    }
+                    (b"trace" | b"event", Some(_)) => {
+                        body = Some(event.into_owned());
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            XmlEvent::End(end) if end.local_name().as_ref() == b"log" => break,
+            XmlEvent::Eof => break,
+            _ => {}
+        }
+        buffer.clear();
+    }
+    Ok((header.ok_or(XesError::NoLogs)?, body))
+}
+
+/// Read the version, features and extensions of the first log in an `XES`-file without
+/// reading its traces and events.
+pub fn peek_header<P: AsRef<Path>>(path: P) -> Result<LogHeader, XesError> {
+    let mut reader = Reader::from_reader(BufReader::new(File::open(path)?));
+    read_header(&mut reader)
+}
+
+/// Element being built while streaming traces.
+enum Frame {
+    Trace(Trace),
+    Event(HashMap<Key, Attribute>),
+    Attribute {
+        key: String,
+        tag: String,
+        value: Option<String>,
+        text: Option<String>,
+        has_children: bool,
+        attributes: HashMap<Key, Attribute>,
+    },
+}
+
+/// Frame for element `start` opened inside `parent`, `None` if it is skipped.
+fn open(start: &BytesStart, parent: &mut Frame) -> Result<Option<Frame>, XesError> {
+    let name = start.local_name();
+    let name = std::str::from_utf8(name.as_ref()).unwrap_or_default();
+    Ok(match (name, parent) {
+        ("event", Frame::Trace(_)) => Some(Frame::Event(HashMap::new())),
+        (tag, parent) if ATTRIBUTE_TAGS.contains(&tag) => {
+            if let Frame::Attribute { has_children, .. } = parent {
+                *has_children = true;
+            }
+            Some(Frame::Attribute {
+                key: required_xml_attribute(start, "key")?,
+                tag: tag.to_owned(),
+                value: xml_attribute(start, "value")?,
+                text: None,
+                has_children: false,
+                attributes: HashMap::new(),
+            })
+        }
+        (_, Frame::Attribute { has_children, .. }) => {
+            *has_children = true;
+            None
+        }
+        _ => None,
+    })
+}
+
+/// Finish the innermost frame of `stack` and add it to its parent, returning it
+/// instead if it is the outermost frame.
+fn close(stack: &mut Vec<Frame>, interner: &mut Interner) -> Result<Option<Frame>, XesError> {
+    let Some(frame) = stack.pop() else {
+        return Ok(None);
+    };
+    let Some(parent) = stack.last_mut() else {
+        return Ok(Some(frame));
+    };
+    match frame {
+        Frame::Trace(_) => {}
+        Frame::Event(attributes) => {
+            if let Frame::Trace(trace) = parent {
+                trace.events.push(Event::new(attributes));
+            }
+        }
+        Frame::Attribute {
+            key,
+            tag,
+            value,
+            text,
+            has_children,
+            attributes,
+        } => {
+            let text = if tag == "string" && !has_children {
+                text
+            } else {
+                None
+            };
+            let attribute = match value.or(text) {
+                Some(value) if !matches!(tag.as_str(), "list" | "container") => {
+                    parse_scalar(&tag, &key, &value, &ReadOptions::default())?
+                }
+                None if !matches!(tag.as_str(), "list" | "container") && attributes.is_empty() => {
+                    default_scalar(&tag)
+                }
+                _ => Attribute::List(attributes),
+            };
+            let key = interner.intern(&key);
+            match parent {
+                Frame::Trace(trace) => trace.attributes.insert(key, attribute),
+                Frame::Event(attributes) | Frame::Attribute { attributes, .. } => {
+                    attributes.insert(key, attribute)
+                }
+            };
+        }
+    }
+    Ok(None)
+}
+
+/// Read the contents of the element whose start tag was just read from `reader` into
+/// `root`, up to and including its end tag.
+fn read_element<R: BufRead>(
+    reader: &mut Reader<R>,
+    root: Frame,
+    interner: &mut Interner,
+) -> Result<Frame, XesError> {
+    let mut stack = vec![root];
+    let mut buffer = Vec::new();
+    let mut skipped = Vec::new();
+    loop {
+        let parent = stack.last_mut().expect("the root frame is open");
+        match reader.read_event_into(&mut buffer)? {
+            XmlEvent::Start(start) => match open(&start, parent)? {
+                Some(_) if stack.len() > MAX_NESTING_DEPTH => {
+                    return Err(XesError::NestingTooDeep(MAX_NESTING_DEPTH));
+                }
+                Some(frame) => stack.push(frame),
+                None => {
+                    reader.read_to_end_into(start.name(), &mut skipped)?;
+                    skipped.clear();
+                }
+            },
+            XmlEvent::Empty(start) => {
+                if let Some(frame) = open(&start, parent)? {
+                    stack.push(frame);
+                    close(&mut stack, interner)?;
+                }
+            }
+            XmlEvent::Text(text) => {
+                if let Frame::Attribute { text: value, .. } = parent {
+                    value
+                        .get_or_insert_with(String::new)
+                        .push_str(&text.unescape()?);
+                }
+            }
+            XmlEvent::CData(cdata) => {
+                if let Frame::Attribute { text: value, .. } = parent {
+                    value
+                        .get_or_insert_with(String::new)
+                        .push_str(&String::from_utf8_lossy(&cdata));
+                }
+            }
+            XmlEvent::End(_) => {
+                if let Some(root) = close(&mut stack, interner)? {
+                    return Ok(root);
+                }
+            }
+            XmlEvent::Eof => {
+                return Err(quick_xml::Error::UnexpectedEof("element".to_owned()).into());
+            }
+            _ => {}
+        }
+        buffer.clear();
+    }
+}
+
+fn empty_trace() -> Trace {
+    Trace {
+        attributes: HashMap::new(),
+        events: Vec::new(),
+    }
+}
+
+/// Read the traces of the first log in an `XES`-file one at a time, passing each to
+/// `callback` together with the log header.
+///
+/// Only one trace is held in memory at a time. Events directly under the log and
+/// nested logs are skipped, and reading stops at the end of the log, so whatever
+/// follows it, such as trailing blank lines, is not read.
+pub fn read_traces<P, F>(path: P, mut callback: F) -> Result<LogHeader, XesError>
+where
+    P: AsRef<Path>,
+    F: FnMut(&LogHeader, Trace),
+{
+    let mut reader = Reader::from_reader(BufReader::new(File::open(path)?));
+    let (header, body) = read_header_until_body(&mut reader)?;
+    let mut interner = Interner::default();
+    let mut buffer = Vec::new();
+    let mut skipped = Vec::new();
+    let mut next = body;
+    loop {
+        let event = match next.take() {
+            Some(event) => event,
+            None => reader.read_event_into(&mut buffer)?.into_owned(),
+        };
+        match event {
+            XmlEvent::Start(start) if start.local_name().as_ref() == b"trace" => {
+                let root = Frame::Trace(empty_trace());
+                if let Frame::Trace(trace) = read_element(&mut reader, root, &mut interner)? {
+                    callback(&header, trace);
+                }
+            }
+            XmlEvent::Empty(start) if start.local_name().as_ref() == b"trace" => {
+                callback(&header, empty_trace());
+            }
+            XmlEvent::Start(start) => {
+                reader.read_to_end_into(start.name(), &mut skipped)?;
+                skipped.clear();
+            }
+            XmlEvent::End(_) | XmlEvent::Eof => break,
+            _ => {}
+        }
+        buffer.clear();
+    }
+    Ok(header)
+}
+
+/// Copy `XES` from `input` to `output` one event at a time, passing every event to `f`
+/// which may change it and returns whether it is kept.
+///
+/// Everything other than events is copied unchanged and only one event is held in
+/// memory at a time. Kept events are written back as by [`write`](crate::write).
+pub fn transform_stream<R, W, F>(input: R, output: W, mut f: F) -> Result<(), XesError>
+where
+    R: BufRead,
+    W: Write,
+    F: FnMut(&mut Event) -> bool,
+{
+    let mut reader = Reader::from_reader(input);
+    let mut writer = Writer::new(output);
+    let mut interner = Interner::default();
+    let options = WriteOptions::default();
+    let mut buffer = Vec::new();
+    loop {
+        let mut event = match reader.read_event_into(&mut buffer)? {
+            XmlEvent::Start(start) if start.local_name().as_ref() == b"event" => {
+                let root = Frame::Event(HashMap::new());
+                match read_element(&mut reader, root, &mut interner)? {
+                    Frame::Event(attributes) => Event::new(attributes),
+                    _ => unreachable!("the root frame is an event"),
+                }
+            }
+            XmlEvent::Empty(start) if start.local_name().as_ref() == b"event" => Event::default(),
+            XmlEvent::Eof => break,
+            event => {
+                writer.write_event(event)?;
+                buffer.clear();
+                continue;
+            }
+        };
+        if f(&mut event) {
+            let mut events = Vec::new();
+            write_event(&event, None, &options, &mut events);
+            for event in events {
+                writer.write_event(event)?;
+            }
+        }
+        buffer.clear();
+    }
+    Ok(())
+}
+
+/// Read the log at `index`, counting from zero, of an `XES`-file holding several logs,
+/// `None` if it has fewer logs.
+///
+/// Logs are counted in document order as with [`ReadOptions::deep_search`], and the
+/// logs before the wanted one are skipped without being parsed. Namespace declarations
+/// of the elements around the wanted log still apply to it.
+pub fn read_nth<P: AsRef<Path>>(path: P, index: usize) -> Result<Option<Log>, XesError> {
+    let text = std::fs::read_to_string(path)?;
+    let mut reader = Reader::from_str(&text);
+    // Namespace declarations of each open element around the current position.
+    let mut namespaces: Vec<Vec<(String, String)>> = Vec::new();
+    let mut count = 0;
+    loop {
+        let start = reader.buffer_position();
+        let end = match reader.read_event()? {
+            XmlEvent::Start(log) if log.local_name().as_ref() == b"log" => {
+                reader.read_to_end(log.name())?;
+                reader.buffer_position()
+            }
+            XmlEvent::Empty(log) if log.local_name().as_ref() == b"log" => reader.buffer_position(),
+            XmlEvent::Start(element) => {
+                namespaces.push(namespace_declarations(&element)?);
+                continue;
+            }
+            XmlEvent::End(_) => {
+                namespaces.pop();
+                continue;
+            }
+            XmlEvent::Eof => return Ok(None),
+            _ => continue,
+        };
+        if count == index {
+            // Parse the log inside an element redeclaring the namespaces in scope, inner
+            // declarations replacing outer ones.
+            let mut in_scope: Vec<(String, String)> = Vec::new();
+            for (name, value) in namespaces.into_iter().flatten() {
+                in_scope.retain(|(other, _)| *other != name);
+                in_scope.push((name, value));
+            }
+            let mut wrapped = String::from("<read-nth");
+            for (name, value) in in_scope {
+                wrapped.push_str(&format!(
+                    " {name}=\"{}\"",
+                    quick_xml::escape::escape(&value)
+                ));
+            }
+            wrapped.push('>');
+            wrapped.push_str(&text[start..end]);
+            wrapped.push_str("</read-nth>");
+            let options = ReadOptions {
+                deep_search: true,
+                ..ReadOptions::default()
+            };
+            let logs = read_str_with_options(&wrapped, &options)?;
+            return Ok(logs.into_iter().next());
+        }
+        count += 1;
+    }
+}
+
+/// `xmlns` and `xmlns:*` attributes of `element` with their unescaped values.
+fn namespace_declarations(element: &BytesStart) -> Result<Vec<(String, String)>, XesError> {
+    let mut declarations = Vec::new();
+    for attribute in element.attributes() {
+        let attribute = attribute.map_err(quick_xml::Error::from)?;
+        let name = String::from_utf8_lossy(attribute.key.as_ref());
+        if name == "xmlns" || name.starts_with("xmlns:") {
+            declarations.push((name.into_owned(), attribute.unescape_value()?.into_owned()));
+        }
+    }
+    Ok(declarations)
+}
@@ -0,0 +1,242 @@
+use crate::ontology::attribute;
+use crate::Attribute;
+use crate::Event;
+use crate::Key;
+use crate::Log;
+use chrono::DateTime;
+use chrono::FixedOffset;
+use std::collections::HashMap;
+
+/// Problem found while validating a log.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationIssue {
+    /// Event lacks a required attribute. `trace` is `None` for events directly under the log.
+    MissingAttribute {
+        trace: Option<usize>,
+        event: usize,
+        key: String,
+    },
+    /// Trace or event attribute whose type differs from the global declaring it.
+    /// `event` is `None` for trace attributes.
+    TypeMismatch {
+        trace: Option<usize>,
+        event: Option<usize>,
+        key: String,
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// Event whose `time:timestamp` lies outside the time span declared by the log.
+    /// `trace` is `None` for events directly under the log.
+    OutsideTimeSpan {
+        trace: Option<usize>,
+        event: usize,
+        timestamp: DateTime<FixedOffset>,
+    },
+}
+
+/// Whether validation stops at the first issue.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ValidationMode {
+    /// Stop at the first issue, which is cheaper when only validity matters.
+    FailFast,
+    /// Report every issue.
+    #[default]
+    CollectAll,
+}
+
+/// Issues found while validating a log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationReport {
+    mode: ValidationMode,
+    issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    fn new(mode: ValidationMode) -> Self {
+        Self {
+            mode,
+            issues: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, issue: ValidationIssue) {
+        if !self.is_finished() {
+            self.issues.push(issue);
+        }
+    }
+
+    /// Whether no further issues are recorded.
+    fn is_finished(&self) -> bool {
+        self.mode == ValidationMode::FailFast && !self.issues.is_empty()
+    }
+
+    /// Whether no issue was found.
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// First issue found, in document order.
+    pub fn first_error(&self) -> Option<&ValidationIssue> {
+        self.issues.first()
+    }
+
+    /// Every issue found, at most one with [`ValidationMode::FailFast`].
+    pub fn all_issues(&self) -> &[ValidationIssue] {
+        &self.issues
+    }
+
+    /// Move the issues out of this report.
+    pub fn into_issues(self) -> Vec<ValidationIssue> {
+        self.issues
+    }
+}
+
+/// Attributes most miners expect on every event.
+pub const STANDARD_EVENT_ATTRIBUTES: [&str; 2] = ["concept:name", "time:timestamp"];
+
+/// Keys of the log attributes declaring the earliest and latest event timestamp.
+pub const TIME_SPAN_ATTRIBUTES: [&str; 2] = ["time:start", "time:end"];
+
+impl Log {
+    /// Report events that lack one of the `required` attribute keys.
+    pub fn validate_required_attributes(
+        &self,
+        required: &[&str],
+        mode: ValidationMode,
+    ) -> ValidationReport {
+        let mut report = ValidationReport::new(mode);
+        for (trace_index, trace) in self.traces.iter().enumerate() {
+            check_required(&trace.events, Some(trace_index), required, &mut report);
+        }
+        check_required(&self.events, None, required, &mut report);
+        report
+    }
+
+    /// Report events that lack `concept:name` or `time:timestamp`.
+    pub fn validate_standard_attributes(&self, mode: ValidationMode) -> ValidationReport {
+        self.validate_required_attributes(&STANDARD_EVENT_ATTRIBUTES, mode)
+    }
+
+    /// Report trace and event attributes whose type differs from the type of the global
+    /// of the same key.
+    ///
+    /// Elements are checked in document order and the attributes of each element in key
+    /// order, so [`ValidationMode::FailFast`] always reports the same issue.
+    pub fn validate_global_types(&self, mode: ValidationMode) -> ValidationReport {
+        let mut report = ValidationReport::new(mode);
+        for (trace_index, trace) in self.traces.iter().enumerate() {
+            check_types(
+                &trace.attributes,
+                &self.trace_globals,
+                Some(trace_index),
+                None,
+                &mut report,
+            );
+            for (event_index, event) in trace.events.iter().enumerate() {
+                check_types(
+                    &event.attributes,
+                    &self.event_globals,
+                    Some(trace_index),
+                    Some(event_index),
+                    &mut report,
+                );
+            }
+        }
+        for (event_index, event) in self.events.iter().enumerate() {
+            check_types(
+                &event.attributes,
+                &self.event_globals,
+                None,
+                Some(event_index),
+                &mut report,
+            );
+        }
+        report
+    }
+
+    /// Report events timestamped before the log attribute `time:start` or after
+    /// `time:end`, both inclusive. A bound that is missing or not a datetime is not
+    /// checked.
+    pub fn validate_time_span(&self, mode: ValidationMode) -> ValidationReport {
+        let mut report = ValidationReport::new(mode);
+        let [start, end] = TIME_SPAN_ATTRIBUTES
+            .map(|key| self.attributes.get(key).and_then(Attribute::as_datetime));
+        if start.is_none() && end.is_none() {
+            return report;
+        }
+        let events = self
+            .traces
+            .iter()
+            .enumerate()
+            .map(|(trace_index, trace)| (Some(trace_index), &trace.events))
+            .chain([(None, &self.events)]);
+        for (trace, events) in events {
+            for (event_index, event) in events.iter().enumerate() {
+                if report.is_finished() {
+                    return report;
+                }
+                let Some(timestamp) = event.timestamp() else {
+                    continue;
+                };
+                if start.is_some_and(|start| timestamp < start)
+                    || end.is_some_and(|end| timestamp > end)
+                {
+                    report.push(ValidationIssue::OutsideTimeSpan {
+                        trace,
+                        event: event_index,
+                        timestamp,
+                    });
+                }
+            }
+        }
+        report
+    }
+}
+
+fn check_required(
+    events: &[Event],
+    trace: Option<usize>,
+    required: &[&str],
+    report: &mut ValidationReport,
+) {
+    for (event_index, event) in events.iter().enumerate() {
+        for key in required {
+            if report.is_finished() {
+                return;
+            }
+            if !event.attributes.contains_key(*key) {
+                report.push(ValidationIssue::MissingAttribute {
+                    trace,
+                    event: event_index,
+                    key: (*key).to_owned(),
+                });
+            }
+        }
+    }
+}
+
+fn check_types(
+    attributes: &HashMap<Key, Attribute>,
+    globals: &HashMap<Key, Attribute>,
+    trace: Option<usize>,
+    event: Option<usize>,
+    report: &mut ValidationReport,
+) {
+    for (key, global) in attribute::sorted(globals) {
+        if report.is_finished() {
+            return;
+        }
+        let Some(attribute) = attributes.get(key) else {
+            continue;
+        };
+        if attribute.type_name() != global.type_name() {
+            report.push(ValidationIssue::TypeMismatch {
+                trace,
+                event,
+                key: key.to_string(),
+                expected: global.type_name(),
+                found: attribute.type_name(),
+            });
+        }
+    }
+}
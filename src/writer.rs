@@ -0,0 +1,435 @@
+use crate::Attribute;
+use crate::Event;
+use crate::Extension;
+use crate::Key;
+use crate::Log;
+use crate::Trace;
+use crate::XesError;
+use quick_xml::events::BytesEnd;
+use quick_xml::events::BytesStart;
+use quick_xml::events::Event as XmlEvent;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fs::OpenOptions;
+use std::path::Path;
+
+/// Options controlling how a log is serialized.
+#[derive(Debug, Clone, Default)]
+pub struct WriteOptions {
+    /// Keys of log attributes to write, `None` writes all of them.
+    pub log_keys: Option<HashSet<String>>,
+    /// Keys of trace attributes to write, `None` writes all of them.
+    pub trace_keys: Option<HashSet<String>>,
+    /// Keys of event attributes to write, `None` writes all of them.
+    pub event_keys: Option<HashSet<String>>,
+    /// Write the attributes of each element in key order instead of map order.
+    pub sort_attributes: bool,
+    /// Element names written for `Long` and `Double` values.
+    pub number_elements: NumberElements,
+    /// Put every element on its own line, indented by this many spaces per level.
+    /// `None` writes everything on one line.
+    pub indent: Option<usize>,
+    /// Line ending used between indented elements.
+    pub line_ending: LineEnding,
+    /// Leave out trace and event attributes equal to the global of their scope, which
+    /// reading with [`ReadOptions::resolve_globals`](crate::ReadOptions::resolve_globals)
+    /// restores.
+    pub omit_defaults: bool,
+}
+
+/// Line ending written by [`WriteOptions::indent`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+/// Element names written for `Long` and `Double` values.
+///
+/// The `XES` standard, IEEE 1849, names them `<int>` and `<float>`. `<long>` and
+/// `<double>` are not part of the standard, although this crate and some other tools
+/// read them. Datetimes are written as the standard `<date>` either way, `<datetime>`
+/// is only accepted when reading.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NumberElements {
+    /// Numbers are written as `<int>` and `<float>`, as the standard defines.
+    Standard,
+    /// Numbers are written as `<long>` and `<double>`, as earlier versions of this
+    /// crate did.
+    #[default]
+    Legacy,
+}
+
+fn write_extension(extension: &Extension, events: &mut Vec<XmlEvent>) {
+    let mut exte = BytesStart::new("extension");
+    push_attribute(&mut exte, "name", extension.name.as_str());
+    push_attribute(&mut exte, "prefix", extension.prefix.as_str());
+    push_attribute(&mut exte, "uri", extension.uri.as_str());
+    events.push(XmlEvent::Start(exte));
+    events.push(XmlEvent::End(BytesEnd::new("extension")));
+}
+
+/// Append `value` to `content`, escaped for use in a quoted XML attribute value.
+///
+/// Newlines, tabs and carriage returns are escaped too, as readers replace them by
+/// spaces in attribute values.
+fn push_escaped(content: &mut String, value: &str) {
+    let mut start = 0;
+    for (index, byte) in value.bytes().enumerate() {
+        let escaped = match byte {
+            b'<' => "&lt;",
+            b'>' => "&gt;",
+            b'&' => "&amp;",
+            b'"' => "&quot;",
+            b'\'' => "&apos;",
+            b'\n' => "&#10;",
+            b'\t' => "&#9;",
+            b'\r' => "&#13;",
+            _ => continue,
+        };
+        content.push_str(&value[start..index]);
+        content.push_str(escaped);
+        start = index + 1;
+    }
+    content.push_str(&value[start..]);
+}
+
+/// Add attribute `name` with `value` to `element`, escaped as by [`push_escaped`].
+fn push_attribute(element: &mut BytesStart, name: &str, value: &str) {
+    let mut escaped = String::with_capacity(value.len());
+    push_escaped(&mut escaped, value);
+    element.push_attribute((name.as_bytes(), escaped.as_bytes()));
+}
+
+fn write_attribute<'a>(
+    attribute: (&'a Key, &'a Attribute),
+    options: &WriteOptions,
+    events: &mut Vec<XmlEvent<'a>>,
+) {
+    let (k, v) = attribute;
+    let element_name = match v {
+        Attribute::List(_) => "list",
+        Attribute::String(_) => "string",
+        Attribute::Long(_) if options.number_elements == NumberElements::Standard => "int",
+        Attribute::Long(_) => "long",
+        Attribute::Double(_) if options.number_elements == NumberElements::Standard => "float",
+        Attribute::Double(_) => "double",
+        Attribute::DateTime(_) => "date",
+        Attribute::Boolean(_) => "boolean",
+        Attribute::ID(_) => "id",
+    };
+    let mut long = itoa::Buffer::new();
+    let mut double = ryu::Buffer::new();
+    let value = match v {
+        Attribute::List(_) => None,
+        Attribute::String(value) | Attribute::DateTime(value) | Attribute::ID(value) => {
+            Some(value.as_str())
+        }
+        Attribute::Long(value) => Some(long.format(*value)),
+        Attribute::Double(value) => Some(double.format(*value)),
+        Attribute::Boolean(value) => Some(if *value { "true" } else { "false" }),
+    };
+    // The start tag is built in a single allocation.
+    let mut content =
+        String::with_capacity(element_name.len() + k.len() + value.map_or(0, str::len) + 16);
+    content.push_str(element_name);
+    // Some parsers require `key` to come before `value`.
+    content.push_str(" key=\"");
+    push_escaped(&mut content, k);
+    content.push('"');
+    if let Some(value) = value {
+        content.push_str(" value=\"");
+        push_escaped(&mut content, value);
+        content.push('"');
+    }
+    events.push(XmlEvent::Start(BytesStart::from_content(
+        content,
+        element_name.len(),
+    )));
+    if let Attribute::List(list) = v {
+        write_attributes(list, None, None, options, events);
+    }
+    events.push(XmlEvent::End(BytesEnd::new(element_name)));
+}
+
+/// Write `attributes` whose keys are in `keys`, leaving out those equal to their value
+/// in `defaults`.
+fn write_attributes<'a>(
+    attributes: &'a HashMap<Key, Attribute>,
+    keys: Option<&HashSet<String>>,
+    defaults: Option<&HashMap<Key, Attribute>>,
+    options: &WriteOptions,
+    events: &mut Vec<XmlEvent<'a>>,
+) {
+    let selected = attributes
+        .iter()
+        .filter(|(key, _)| keys.is_none_or(|keys| keys.contains(&***key)))
+        .filter(|(key, value)| defaults.is_none_or(|defaults| defaults.get(*key) != Some(value)));
+    if options.sort_attributes {
+        let mut selected: Vec<_> = selected.collect();
+        selected.sort_by(|a, b| a.0.cmp(b.0));
+        for attribute in selected {
+            write_attribute(attribute, options, events);
+        }
+    } else {
+        for attribute in selected {
+            write_attribute(attribute, options, events);
+        }
+    }
+}
+
+pub(crate) fn write_event<'a>(
+    event: &'a Event,
+    globals: Option<&HashMap<Key, Attribute>>,
+    options: &WriteOptions,
+    events: &mut Vec<XmlEvent<'a>>,
+) {
+    events.push(XmlEvent::Start(BytesStart::new("event")));
+    write_attributes(
+        &event.attributes,
+        options.event_keys.as_ref(),
+        globals,
+        options,
+        events,
+    );
+    events.push(XmlEvent::End(BytesEnd::new("event")));
+}
+
+/// Write `trace`, leaving out attributes equal to the globals of `defaults`.
+fn write_trace<'a>(
+    trace: &'a Trace,
+    defaults: Option<&Log>,
+    options: &WriteOptions,
+    events: &mut Vec<XmlEvent<'a>>,
+) {
+    events.push(XmlEvent::Start(BytesStart::new("trace")));
+    write_attributes(
+        &trace.attributes,
+        options.trace_keys.as_ref(),
+        defaults.map(|log| &log.trace_globals),
+        options,
+        events,
+    );
+    for event in &trace.events {
+        write_event(
+            event,
+            defaults.map(|log| &log.event_globals),
+            options,
+            events,
+        );
+    }
+    events.push(XmlEvent::End(BytesEnd::new("trace")));
+}
+
+fn write_log<'a>(log: &'a Log, options: &WriteOptions, events: &mut Vec<XmlEvent<'a>>) {
+    let mut loge = BytesStart::new("log");
+    push_attribute(&mut loge, "version", log.version.as_str());
+    push_attribute(&mut loge, "features", log.features.join(",").as_str());
+    // `version` and `features` come first, then namespace declarations and then other
+    // attributes, each in stored order.
+    let is_namespace = |name: &str| name == "xmlns" || name.starts_with("xmlns:");
+    let (namespaces, others): (Vec<_>, Vec<_>) = log
+        .xml_attributes
+        .iter()
+        .filter(|(name, _)| !matches!(name.as_str(), "version" | "features"))
+        .partition(|(name, _)| is_namespace(name));
+    for (name, value) in namespaces.into_iter().chain(others) {
+        push_attribute(&mut loge, name.as_str(), value.as_str());
+    }
+    events.push(XmlEvent::Start(loge));
+    for extension in &log.extensions {
+        write_extension(extension, events);
+    }
+    for (scope, globals) in [("trace", &log.trace_globals), ("event", &log.event_globals)] {
+        if globals.is_empty() {
+            continue;
+        }
+        let mut globale = BytesStart::new("global");
+        push_attribute(&mut globale, "scope", scope);
+        events.push(XmlEvent::Start(globale));
+        write_attributes(globals, None, None, options, events);
+        events.push(XmlEvent::End(BytesEnd::new("global")));
+    }
+    for classifier in &log.classifiers {
+        let mut classifiere = BytesStart::new("classifier");
+        push_attribute(&mut classifiere, "name", classifier.name.as_str());
+        if let Some(scope) = &classifier.scope {
+            push_attribute(&mut classifiere, "scope", scope.as_str());
+        }
+        push_attribute(
+            &mut classifiere,
+            "keys",
+            classifier.keys_attribute().as_str(),
+        );
+        events.push(XmlEvent::Start(classifiere));
+        events.push(XmlEvent::End(BytesEnd::new("classifier")));
+    }
+    write_attributes(
+        &log.attributes,
+        options.log_keys.as_ref(),
+        None,
+        options,
+        events,
+    );
+    let defaults = options.omit_defaults.then_some(log);
+    for trace in &log.traces {
+        write_trace(trace, defaults, options, events);
+    }
+    for event in &log.events {
+        write_event(
+            event,
+            defaults.map(|log| &log.event_globals),
+            options,
+            events,
+        );
+    }
+    for sub_log in &log.sub_logs {
+        write_log(sub_log, options, events);
+    }
+    events.push(XmlEvent::End(BytesEnd::new("log")));
+}
+
+/// Transform Rust representation to `XES`-text.
+pub fn write_string(log: &Log) -> String {
+    write_string_with_options(log, &WriteOptions::default())
+}
+
+/// Transform Rust representation to `XES`-text using `options`.
+pub fn write_string_with_options(log: &Log, options: &WriteOptions) -> String {
+    let mut events = Vec::new();
+    write_log(log, options, &mut events);
+    serialize(events, options)
+}
+
+fn serialize(events: Vec<XmlEvent>, options: &WriteOptions) -> String {
+    use quick_xml::events::BytesText;
+    use quick_xml::Writer;
+    use std::io::Cursor;
+    let mut writer = Writer::new(Cursor::new(Vec::new()));
+    let newline = match options.line_ending {
+        LineEnding::Lf => "\n",
+        LineEnding::CrLf => "\r\n",
+    };
+    let mut depth = 0;
+    let mut previous_was_start = false;
+    for event in events {
+        if let Some(indent) = options.indent {
+            let is_start = matches!(event, XmlEvent::Start(_));
+            let breaks = match event {
+                XmlEvent::Start(_) => {
+                    depth += 1;
+                    depth > 1
+                }
+                XmlEvent::End(_) => {
+                    depth -= 1;
+                    !previous_was_start
+                }
+                _ => false,
+            };
+            if breaks {
+                let level = if is_start { depth - 1 } else { depth };
+                let text = format!("{newline}{}", " ".repeat(indent * level));
+                writer
+                    .write_event(XmlEvent::Text(BytesText::from_escaped(text)))
+                    .expect("writing to memory does not fail");
+            }
+            previous_was_start = is_start;
+        }
+        writer
+            .write_event(event)
+            .expect("writing to memory does not fail");
+    }
+    let contents = writer.into_inner().into_inner();
+    String::from_utf8(contents).expect("writer emits UTF-8")
+}
+
+/// Transform Rust representation to `XES`-file.
+pub fn write<P: AsRef<Path>>(log: &Log, path: P) -> Result<(), XesError> {
+    write_with_options(log, path, &WriteOptions::default())
+}
+
+/// Transform Rust representation to `XES`-file using `options`.
+pub fn write_with_options<P: AsRef<Path>>(
+    log: &Log,
+    path: P,
+    options: &WriteOptions,
+) -> Result<(), XesError> {
+    std::fs::write(path, write_string_with_options(log, options))?;
+    Ok(())
+}
+
+/// Append `events` directly under the last log of an existing `XES`-file.
+///
+/// The file is scanned without building a tree to find where its last log ends, and
+/// only what follows is rewritten: the new events are inserted before the final
+/// `</log>`, or a self-closing `<log/>` is expanded to hold them, and whatever follows
+/// the log, such as comments or trailing whitespace, is kept. The file only grows, so
+/// no data is removed before the new bytes are written. A file without logs is
+/// rejected with [`XesError::NoLogs`].
+pub fn append_events<P: AsRef<Path>>(path: P, events: &[Event]) -> Result<(), XesError> {
+    use std::io::Read;
+    use std::io::Seek;
+    use std::io::SeekFrom;
+    use std::io::Write;
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    let options = WriteOptions::default();
+    let mut xml = Vec::new();
+    for event in events {
+        write_event(event, None, &options, &mut xml);
+    }
+    let mut content = Vec::new();
+    let (position, tail_start) = match last_log_end(&file)? {
+        LogEnd::EndTag(position) => (position, position),
+        LogEnd::Empty { start, end, name } => {
+            // `<log .../>` becomes `<log ...>`, the events and `</log>`.
+            file.seek(SeekFrom::Start(start))?;
+            (&file).take(end - start).read_to_end(&mut content)?;
+            content.truncate(content.len() - 2);
+            content.push(b'>');
+            xml.push(XmlEvent::End(BytesEnd::new(name)));
+            (start, end)
+        }
+    };
+    content.extend_from_slice(serialize(xml, &options).as_bytes());
+    file.seek(SeekFrom::Start(tail_start))?;
+    (&file).read_to_end(&mut content)?;
+    file.seek(SeekFrom::Start(position))?;
+    file.write_all(&content)?;
+    Ok(())
+}
+
+/// End of the last log of an `XES`-file, see [`last_log_end`].
+enum LogEnd {
+    /// Offset of the `</log>` end tag.
+    EndTag(u64),
+    /// Offsets of a self-closing `<log/>` element, and its name such as `log`.
+    Empty { start: u64, end: u64, name: String },
+}
+
+/// Where the log that ends last in `file` ends, ignoring markup inside comments and
+/// CDATA sections.
+fn last_log_end(file: &std::fs::File) -> Result<LogEnd, XesError> {
+    let mut reader = quick_xml::Reader::from_reader(std::io::BufReader::new(file));
+    let mut buffer = Vec::new();
+    let mut last = None;
+    loop {
+        let start = reader.buffer_position() as u64;
+        match reader.read_event_into(&mut buffer)? {
+            XmlEvent::End(end) if end.local_name().as_ref() == b"log" => {
+                last = Some(LogEnd::EndTag(start));
+            }
+            XmlEvent::Empty(log) if log.local_name().as_ref() == b"log" => {
+                last = Some(LogEnd::Empty {
+                    start,
+                    end: reader.buffer_position() as u64,
+                    name: String::from_utf8_lossy(log.name().as_ref()).into_owned(),
+                });
+            }
+            XmlEvent::Eof => break,
+            _ => {}
+        }
+        buffer.clear();
+    }
+    last.ok_or(XesError::NoLogs)
+}
@@ -0,0 +1,59 @@
+use std::cmp::Ordering;
+use xes::Attribute;
+
+#[test]
+fn long_and_double_compare_numerically() {
+    assert_eq!(
+        Attribute::Long(2).partial_cmp_value(&Attribute::Double(2.5)),
+        Some(Ordering::Less)
+    );
+    assert_eq!(
+        Attribute::Double(3.0).partial_cmp_value(&Attribute::Long(3)),
+        Some(Ordering::Equal)
+    );
+}
+
+#[test]
+fn string_and_long_are_not_comparable() {
+    assert_eq!(
+        Attribute::String("10".to_owned()).partial_cmp_value(&Attribute::Long(10)),
+        None
+    );
+    assert_eq!(
+        Attribute::String("a".to_owned()).partial_cmp_value(&Attribute::String("b".to_owned())),
+        Some(Ordering::Less)
+    );
+}
+
+#[test]
+fn list_from_pairs_is_written_as_a_list() {
+    let costs = Attribute::list_from(vec![
+        ("amount", Attribute::Double(2.5)),
+        ("currency", Attribute::String("EUR".to_owned())),
+    ]);
+    let mut log = xes::read_str(r#"<log version="2.0"><trace><event/></trace></log>"#)
+        .unwrap()
+        .remove(0);
+    log.traces[0].events[0]
+        .attributes
+        .insert("cost:drivers".into(), costs.clone());
+    let text = xes::write_string(&log);
+    assert!(text.contains(r#"<list key="cost:drivers">"#));
+    assert!(text.contains(r#"<string key="currency" value="EUR"></string>"#));
+    let reread = xes::read_str(&text).unwrap().remove(0);
+    assert_eq!(reread.traces[0].events[0].attributes["cost:drivers"], costs);
+}
+
+#[cfg(not(feature = "intern"))]
+#[test]
+fn default_keys_are_strings() {
+    let list: std::collections::HashMap<String, Attribute> =
+        [("amount".to_owned(), Attribute::Long(3))].into();
+    let mut log = xes::read_str(r#"<log version="2.0"><trace><event/></trace></log>"#)
+        .unwrap()
+        .remove(0);
+    let attributes = &mut log.traces[0].events[0].attributes;
+    attributes.insert(String::from("cost:drivers"), Attribute::List(list));
+    let key: &String = attributes.keys().next().unwrap();
+    assert_eq!(key, "cost:drivers");
+}
@@ -0,0 +1,19 @@
+#![cfg(feature = "bincode")]
+
+use xes::Log;
+use xes::XesError;
+
+#[test]
+fn bincode_round_trip() {
+    let mut log = Log::synthetic(5, 4, 7);
+    log.traces[0].events[0].attributes.insert(
+        xes::Key::from("cost:drivers"),
+        xes::Attribute::list_from([("amount", xes::Attribute::Double(2.5))]),
+    );
+    let bytes = log.to_bincode().unwrap();
+    assert_eq!(Log::from_bincode(&bytes).unwrap(), log);
+    assert!(matches!(
+        Log::from_bincode(&bytes[..bytes.len() / 2]),
+        Err(XesError::Bincode(_))
+    ));
+}
@@ -0,0 +1,48 @@
+use std::error::Error;
+use xes::XesError;
+
+/// Short name of the category of `error`.
+fn category(error: &XesError) -> &'static str {
+    match error {
+        XesError::Io(_) => "io",
+        XesError::Xml(_) | XesError::XmlStream(_) => "xml",
+        XesError::MissingAttribute { .. } | XesError::InvalidValue { .. } => "semantic",
+        XesError::NoLogs => "empty",
+        _ => "other",
+    }
+}
+
+#[test]
+fn errors_fall_into_matchable_categories() {
+    let missing = xes::read(concat!(env!("CARGO_TARGET_TMPDIR"), "/missing.xes")).unwrap_err();
+    assert_eq!(category(&missing), "io");
+    assert!(missing.source().is_some());
+
+    let malformed = xes::read_str("<log><trace></log>").unwrap_err();
+    assert_eq!(category(&malformed), "xml");
+    assert!(malformed.to_string().starts_with("XML error: "));
+
+    let keyless = xes::read_str(r#"<log version="2.0"><string value="a"/></log>"#).unwrap_err();
+    assert!(matches!(
+        &keyless,
+        XesError::MissingAttribute {
+            element,
+            attribute: "key",
+        } if element == "string"
+    ));
+    assert_eq!(category(&keyless), "semantic");
+    assert!(keyless.source().is_none());
+
+    let invalid =
+        xes::read_str(r#"<log version="2.0"><int key="n" value="one"/></log>"#).unwrap_err();
+    assert!(matches!(
+        &invalid,
+        XesError::InvalidValue { key, value } if key == "n" && value == "one"
+    ));
+    assert_eq!(
+        invalid.to_string(),
+        r#"invalid value "one" for attribute "n""#
+    );
+
+    assert_eq!(category(&xes::read_str("").unwrap_err()), "empty");
+}
@@ -0,0 +1,113 @@
+use chrono::DateTime;
+use xes::Attribute;
+use xes::Event;
+use xes::Key;
+
+/// Event whose content is `content`.
+fn event(content: &str) -> Event {
+    let text = format!(r#"<log version="2.0"><trace><event>{content}</event></trace></log>"#);
+    xes::read_str(&text).unwrap().remove(0).traces[0].events[0].clone()
+}
+
+#[test]
+fn get_many_returns_none_for_absent_keys() {
+    let event =
+        event(r#"<string key="concept:name" value="a"/><long key="cost:total" value="3"/>"#);
+    assert_eq!(
+        event.get_many(&["cost:total", "org:resource", "concept:name"]),
+        [
+            Some(&Attribute::Long(3)),
+            None,
+            Some(&Attribute::String("a".to_owned()))
+        ]
+    );
+}
+
+#[test]
+fn lazy_timestamp_matches_eager_parsing() {
+    let mut event = event(r#"<date key="time:timestamp" value="2020-03-04T05:06:07.5+02:00"/>"#);
+    let eager = DateTime::parse_from_rfc3339("2020-03-04T05:06:07.5+02:00").unwrap();
+    assert_eq!(event.timestamp(), Some(eager));
+    assert_eq!(event.timestamp(), Some(eager));
+    event.attributes.insert(
+        Key::from("time:timestamp"),
+        Attribute::DateTime("2021-01-01T00:00:00Z".to_owned()),
+    );
+    let changed = DateTime::parse_from_rfc3339("2021-01-01T00:00:00Z").unwrap();
+    assert_eq!(event.timestamp(), Some(changed));
+    if let Some(Attribute::DateTime(value)) = event.attributes.get_mut("time:timestamp") {
+        value.replace_range(3..4, "2");
+    }
+    let edited = DateTime::parse_from_rfc3339("2022-01-01T00:00:00Z").unwrap();
+    assert_eq!(event.timestamp(), Some(edited));
+    event.attributes.remove("time:timestamp");
+    assert_eq!(event.timestamp(), None);
+}
+
+#[test]
+fn flattened_attributes_have_key_paths() {
+    let event = event(
+        r#"<string key="concept:name" value="a"/>
+        <list key="cost:drivers">
+            <string key="cost:type" value="labour"/>
+            <list key="detail"><long key="hours" value="3"/></list>
+        </list>"#,
+    );
+    let mut leaves: Vec<_> = event.attributes_flat().collect();
+    leaves.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        leaves,
+        [
+            (
+                "concept:name".to_owned(),
+                &Attribute::String("a".to_owned())
+            ),
+            (
+                "cost:drivers/cost:type".to_owned(),
+                &Attribute::String("labour".to_owned())
+            ),
+            ("cost:drivers/detail/hours".to_owned(), &Attribute::Long(3)),
+        ]
+    );
+}
+
+#[test]
+fn attributes_sorted_ignores_insertion_order() {
+    let mut forward = Event::new(Default::default());
+    let mut backward = Event::new(Default::default());
+    let keys = ["b", "concept:name", "a", "time:timestamp", "Z"];
+    for key in keys {
+        forward
+            .attributes
+            .insert(Key::from(key), Attribute::String(key.to_owned()));
+    }
+    for key in keys.iter().rev() {
+        backward
+            .attributes
+            .insert(Key::from(*key), Attribute::String((*key).to_owned()));
+    }
+    let sorted: Vec<_> = forward
+        .attributes_sorted()
+        .into_iter()
+        .map(|(key, _)| key.as_str())
+        .collect();
+    assert_eq!(sorted, ["Z", "a", "b", "concept:name", "time:timestamp"]);
+    assert_eq!(forward.attributes_sorted(), backward.attributes_sorted());
+}
+
+#[test]
+fn string_value_in_cdata_is_read_verbatim() {
+    let event = event(r#"<string key="note"><![CDATA[<b>5 > 3 & "quoted"</b>]]></string>"#);
+    assert_eq!(
+        event.attributes["note"],
+        Attribute::String(r#"<b>5 > 3 & "quoted"</b>"#.to_owned())
+    );
+}
+
+#[test]
+fn scalar_without_value_or_children_is_empty() {
+    let event = event(r#"<string key="x"/><id key="i"/><list key="l"/>"#);
+    assert_eq!(event.attributes["x"], Attribute::String(String::new()));
+    assert_eq!(event.attributes["i"], Attribute::ID(String::new()));
+    assert_eq!(event.attributes["l"], Attribute::list_from::<Key, _>([]));
+}
@@ -0,0 +1,39 @@
+use xes::Extension;
+use xes::Log;
+use xes::StandardExtension;
+
+#[test]
+fn standard_concept_extension_has_official_uri() {
+    let extension = Extension::standard("Concept").unwrap();
+    assert_eq!(extension.prefix, "concept");
+    assert_eq!(extension.uri, "http://www.xes-standard.org/concept.xesext");
+    assert_eq!(StandardExtension::Concept.extension(), extension);
+    assert!(Extension::standard("Unknown").is_none());
+}
+
+#[test]
+fn standard_extension_is_declared_once() {
+    let mut log = Log::minimal();
+    log.add_standard_extension(StandardExtension::Time);
+    log.add_standard_extension(StandardExtension::Time);
+    let time = StandardExtension::Time.extension();
+    assert_eq!(log.extensions.iter().filter(|e| **e == time).count(), 1);
+}
+
+#[test]
+fn colon_in_a_key_is_a_prefix_only_when_declared() {
+    let log = xes::read_str(
+        r#"<log version="2.0">
+        <extension name="Concept" prefix="concept" uri="http://www.xes-standard.org/concept.xesext"/>
+    </log>"#,
+    )
+    .unwrap()
+    .remove(0);
+    assert_eq!(
+        log.extension_of_key("concept:name")
+            .map(|e| e.name.as_str()),
+        Some("Concept")
+    );
+    assert!(log.extension_of_key("meta_concept:named_events").is_none());
+    assert!(log.extension_of_key("concept").is_none());
+}
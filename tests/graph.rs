@@ -0,0 +1,39 @@
+#![cfg(feature = "petgraph")]
+
+use xes::Log;
+
+fn log_of_activities(traces: &[&[&str]]) -> Log {
+    let mut text = String::from(r#"<log version="2.0">"#);
+    for activities in traces {
+        text.push_str("<trace>");
+        for activity in *activities {
+            text.push_str(&format!(
+                r#"<event><string key="concept:name" value="{activity}"/></event>"#
+            ));
+        }
+        text.push_str("</trace>");
+    }
+    text.push_str("</log>");
+    xes::read_str(&text).unwrap().remove(0)
+}
+
+#[test]
+fn dfg_graph_has_a_node_per_activity_and_an_edge_per_pair() {
+    let log = log_of_activities(&[&["a", "b", "c"], &["a", "b", "b"], &["d"]]);
+    let graph = log.to_dfg_graph();
+    assert_eq!(graph.node_count(), 4);
+    assert_eq!(graph.edge_count(), 3);
+    let mut edges: Vec<_> = graph
+        .raw_edges()
+        .iter()
+        .map(|edge| {
+            (
+                graph[edge.source()].as_str(),
+                graph[edge.target()].as_str(),
+                edge.weight,
+            )
+        })
+        .collect();
+    edges.sort();
+    assert_eq!(edges, [("a", "b", 2), ("b", "b", 1), ("b", "c", 1)]);
+}
@@ -0,0 +1,677 @@
+use chrono::Duration;
+use chrono::TimeZone;
+use chrono::Utc;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use xes::Attribute;
+use xes::Log;
+use xes::MissingResource;
+use xes::StandardExtension;
+use xes::TimezoneOffset;
+
+fn log(text: &str) -> Log {
+    xes::read_str(text).unwrap().remove(0)
+}
+
+/// `<event>` element with a `<string>` attribute for each of `attributes`.
+fn event(attributes: &[(&str, &str)]) -> String {
+    let mut text = String::from("<event>");
+    for (key, value) in attributes {
+        text.push_str(&format!(r#"<string key="{key}" value="{value}"/>"#));
+    }
+    text.push_str("</event>");
+    text
+}
+
+/// Log with a trace per entry of `traces`, each holding events with the given activities.
+fn log_of_activities(traces: &[&[&str]]) -> Log {
+    let mut text = String::from(r#"<log version="2.0">"#);
+    for activities in traces {
+        text.push_str("<trace>");
+        for activity in *activities {
+            text.push_str(&format!(
+                r#"<event><string key="concept:name" value="{activity}"/></event>"#
+            ));
+        }
+        text.push_str("</trace>");
+    }
+    text.push_str("</log>");
+    log(&text)
+}
+
+#[test]
+fn extract_trace_keeps_extensions() {
+    let log = log(r#"<log version="2.0">
+        <extension name="Concept" prefix="concept" uri="http://www.xes-standard.org/concept.xesext"/>
+        <extension name="Time" prefix="time" uri="http://www.xes-standard.org/time.xesext"/>
+        <trace><string key="concept:name" value="0"/></trace>
+        <trace><string key="concept:name" value="1"/></trace>
+        <trace><string key="concept:name" value="2"/></trace>
+    </log>"#);
+    let extracted = log.extract_trace(1).unwrap();
+    assert_eq!(extracted.traces.len(), 1);
+    assert_eq!(extracted.traces[0].name(), Some("1"));
+    assert_eq!(extracted.extensions, log.extensions);
+    assert!(log.extract_trace(3).is_none());
+}
+
+#[test]
+fn out_of_order_event_is_flagged() {
+    let log = log(r#"<log version="2.0">
+        <trace>
+            <event><date key="time:timestamp" value="2020-01-01T00:00:00Z"/></event>
+            <event><date key="time:timestamp" value="2020-01-01T02:00:00Z"/></event>
+        </trace>
+        <trace>
+            <event><date key="time:timestamp" value="2020-01-01T00:00:00Z"/></event>
+            <event><date key="time:timestamp" value="2020-01-01T02:00:00Z"/></event>
+            <event><date key="time:timestamp" value="2020-01-01T01:00:00Z"/></event>
+        </trace>
+    </log>"#);
+    assert_eq!(log.check_timestamp_monotonicity(), [(1, 2)]);
+}
+
+#[test]
+fn traces_sharing_a_variant_are_counted_together() {
+    let log = log_of_activities(&[&["a", "b"], &["a", "b"], &["a", "c"]]);
+    let frequencies = log.variant_frequencies();
+    assert_eq!(frequencies.len(), 2);
+    assert_eq!(frequencies[&vec!["a".to_owned(), "b".to_owned()]], 2);
+    assert_eq!(frequencies[&vec!["a".to_owned(), "c".to_owned()]], 1);
+    assert_eq!(log.traces[0].variant_key(), log.traces[1].variant_key());
+    assert_ne!(log.traces[0].variant_key(), log.traces[2].variant_key());
+}
+
+#[test]
+fn into_traces_and_into_events_move_the_data_out() {
+    let log = log_of_activities(&[&["a", "b"], &["c"]]);
+    let expected = log.traces.clone();
+    let traces = log.into_traces();
+    assert_eq!(traces, expected);
+    let events = traces[0].clone().into_events();
+    assert_eq!(events, traces[0].events);
+    assert_eq!(events[1].name(), Some("b"));
+}
+
+#[test]
+fn resource_frequencies_count_events_per_resource() {
+    let log = log(&format!(
+        r#"<log version="2.0"><trace>{}{}{}</trace>{}</log>"#,
+        event(&[("concept:name", "a"), ("org:resource", "ann")]),
+        event(&[("concept:name", "b"), ("org:resource", "bob")]),
+        event(&[("concept:name", "c")]),
+        event(&[("concept:name", "d"), ("org:resource", "ann")]),
+    ));
+    let event = &log.traces[0].events[0];
+    assert_eq!(event.resource(), Some("ann"));
+    assert_eq!(log.traces[0].events[2].resource(), None);
+    assert_eq!(
+        log.resource_frequencies(),
+        HashMap::from([("ann".to_owned(), 2), ("bob".to_owned(), 1)])
+    );
+}
+
+#[test]
+fn handover_of_work_counts_consecutive_resources() {
+    let log = log(&format!(
+        r#"<log version="2.0"><trace>{}{}{}{}</trace></log>"#,
+        event(&[("org:resource", "ann")]),
+        event(&[("org:resource", "bob")]),
+        event(&[]),
+        event(&[("org:resource", "cid")]),
+    ));
+    let pair = |from: &str, to: &str| (from.to_owned(), to.to_owned());
+    assert_eq!(
+        log.handover_of_work(MissingResource::Skip),
+        HashMap::from([(pair("ann", "bob"), 1), (pair("bob", "cid"), 1)])
+    );
+    assert_eq!(
+        log.handover_of_work(MissingResource::BreakChain),
+        HashMap::from([(pair("ann", "bob"), 1)])
+    );
+}
+
+#[test]
+fn tree_string_shows_the_structure() {
+    let log = log(r#"<log version="2.0">
+        <string key="concept:name" value="L"/>
+        <trace>
+            <string key="concept:name" value="c1"/>
+            <event>
+                <string key="concept:name" value="a"/>
+                <list key="cost:drivers"><double key="x" value="1.5"/></list>
+                <boolean key="done" value="true"/>
+            </event>
+        </trace>
+    </log>"#);
+    assert_eq!(
+        log.to_tree_string(),
+        concat!(
+            "Log (v2.0)\n",
+            "  concept:name=L\n",
+            "  Trace concept:name=c1\n",
+            "    Event concept:name=a\n",
+            "      cost:drivers\n",
+            "        x=1.5\n",
+            "      done=true\n",
+        )
+    );
+}
+
+#[test]
+fn total_cost_sums_long_and_double_amounts() {
+    let log = log(r#"<log version="2.0">
+        <trace>
+            <event><long key="cost:amount" value="10"/></event>
+            <event><double key="cost:amount" value="2.5"/></event>
+            <event><string key="cost:amount" value="ignored"/></event>
+        </trace>
+        <trace><event/></trace>
+        <event><double key="cost:amount" value="0.5"/></event>
+    </log>"#);
+    assert_eq!(log.traces[0].total_cost(), Some(12.5));
+    assert_eq!(log.traces[1].total_cost(), None);
+    assert_eq!(log.total_cost(), Some(13.0));
+}
+
+#[test]
+fn arrival_rate_buckets_traces_by_start_hour() {
+    let log = log(r#"<log version="2.0">
+        <trace><event><date key="time:timestamp" value="2020-01-01T10:15:00Z"/></event></trace>
+        <trace><event><date key="time:timestamp" value="2020-01-01T10:59:59Z"/></event></trace>
+        <trace><event><date key="time:timestamp" value="2020-01-01T12:30:00+01:00"/></event></trace>
+        <trace><event/></trace>
+    </log>"#);
+    let hour = |hour: u32| Utc.with_ymd_and_hms(2020, 1, 1, hour, 0, 0).unwrap();
+    assert_eq!(
+        log.arrival_rate(Duration::hours(1)),
+        BTreeMap::from([(hour(10), 2), (hour(11), 1)])
+    );
+}
+
+#[test]
+fn trace_attribute_overrides_log_attribute() {
+    let log = log(r#"<log version="2.0">
+        <string key="org:group" value="sales"/>
+        <string key="region" value="north"/>
+        <trace><string key="org:group" value="support"/></trace>
+    </log>"#);
+    let attributes = log.effective_trace_attributes(0).unwrap();
+    assert_eq!(attributes["org:group"].as_str(), Some("support"));
+    assert_eq!(attributes["region"].as_str(), Some("north"));
+    assert_eq!(log.attributes["org:group"].as_str(), Some("sales"));
+    assert!(log.effective_trace_attributes(1).is_none());
+}
+
+#[test]
+fn minimal_log_survives_a_round_trip() {
+    let log = Log::minimal();
+    let reread = xes::read_str(&xes::write_string(&log)).unwrap();
+    assert_eq!(reread, [log]);
+    assert_eq!(reread[0].version, "2.0");
+    assert_eq!(
+        reread[0].extensions,
+        [StandardExtension::Concept.extension()]
+    );
+}
+
+#[test]
+fn events_are_grouped_into_traces_by_key() {
+    let mut log = log(&format!(
+        r#"<log version="2.0">{}{}{}{}</log>"#,
+        event(&[("case", "1"), ("concept:name", "a")]),
+        event(&[("case", "2"), ("concept:name", "b")]),
+        event(&[("case", "1"), ("concept:name", "c")]),
+        event(&[("case", "2"), ("concept:name", "d")]),
+    ));
+    log.group_events_into_traces("case");
+    assert!(log.events.is_empty());
+    let traces: Vec<_> = log
+        .traces
+        .iter()
+        .map(|trace| (trace.name(), trace.activities()))
+        .collect();
+    assert_eq!(
+        traces,
+        [(Some("1"), vec!["a", "c"]), (Some("2"), vec!["b", "d"])]
+    );
+}
+
+#[test]
+fn rename_prefix_updates_extension_and_keys() {
+    let mut log = log(r#"<log version="2.0">
+        <extension name="Organizational" prefix="org" uri="http://www.xes-standard.org/org.xesext"/>
+        <classifier name="Resource" keys="org:resource concept:name"/>
+        <string key="org:group" value="g"/>
+        <trace>
+            <event>
+                <string key="org:resource" value="r"/>
+                <list key="details"><string key="org:role" value="clerk"/></list>
+                <string key="orgless" value="x"/>
+            </event>
+        </trace>
+    </log>"#);
+    log.rename_prefix("org", "organization");
+    assert_eq!(log.extensions[0].prefix, "organization");
+    assert!(log.attributes.contains_key("organization:group"));
+    let event = &log.traces[0].events[0];
+    assert!(event.attributes.contains_key("organization:resource"));
+    assert!(!event.attributes.contains_key("org:resource"));
+    assert!(event.attributes.contains_key("orgless"));
+    let Some(xes::Attribute::List(details)) = event.attributes.get("details") else {
+        panic!("details is a list");
+    };
+    assert!(details.contains_key("organization:role"));
+    assert_eq!(
+        log.classifiers[0].keys,
+        ["organization:resource", "concept:name"]
+    );
+}
+
+#[test]
+fn estimated_xml_size_is_close_to_written_length() {
+    let log = Log::synthetic(50, 10, 7);
+    let estimate = log.estimated_xml_size() as f64;
+    let actual = xes::write_string(&log).len() as f64;
+    assert!(
+        (0.5 * actual..=2.0 * actual).contains(&estimate),
+        "estimate {estimate} for {actual} bytes"
+    );
+}
+
+#[test]
+fn lifecycle_transitions_collects_distinct_values() {
+    let log = log(&format!(
+        r#"<log version="2.0"><trace>{}{}{}{}</trace></log>"#,
+        event(&[("concept:name", "a"), ("lifecycle:transition", "start")]),
+        event(&[("concept:name", "a"), ("lifecycle:transition", "complete")]),
+        event(&[("concept:name", "b"), ("lifecycle:transition", "complete")]),
+        event(&[("concept:name", "c")]),
+    ));
+    assert_eq!(
+        log.lifecycle_transitions(),
+        HashSet::from(["start".to_owned(), "complete".to_owned()])
+    );
+}
+
+#[test]
+fn reformat_timestamps_rewrites_datetime_values() {
+    let mut log = log(r#"<log version="2.0">
+        <trace>
+            <date key="deadline" value="2020-03-04T05:06:07Z"/>
+            <event>
+                <date key="time:timestamp" value="2020-01-02T23:30:00+01:00"/>
+                <date key="bad" value="not a date"/>
+                <string key="text" value="2020-01-02T00:00:00Z"/>
+            </event>
+        </trace>
+    </log>"#);
+    log.reformat_timestamps("%Y-%m-%d");
+    let trace = &log.traces[0];
+    assert_eq!(
+        trace.attributes["deadline"],
+        Attribute::DateTime("2020-03-04".to_owned())
+    );
+    let event = &trace.events[0];
+    assert_eq!(
+        event.attributes["time:timestamp"],
+        Attribute::DateTime("2020-01-02".to_owned())
+    );
+    assert_eq!(
+        event.attributes["bad"],
+        Attribute::DateTime("not a date".to_owned())
+    );
+    assert_eq!(
+        event.attributes["text"],
+        Attribute::String("2020-01-02T00:00:00Z".to_owned())
+    );
+}
+
+#[test]
+fn case_index_finds_traces_by_name() {
+    let log = log(r#"<log version="2.0">
+        <trace><string key="concept:name" value="case-1"/></trace>
+        <trace/>
+        <trace><string key="concept:name" value="case-2"/></trace>
+        <trace><string key="concept:name" value="case-1"/></trace>
+    </log>"#);
+    let index = log.build_case_index();
+    assert_eq!(
+        index,
+        HashMap::from([("case-1".to_owned(), 0), ("case-2".to_owned(), 2)])
+    );
+    assert_eq!(log.trace_index_by_name("case-2"), Some(2));
+    assert_eq!(log.trace_index_by_name("case-3"), None);
+    assert_eq!(log.duplicate_case_ids(), ["case-1"]);
+}
+
+#[test]
+fn start_and_end_activities_count_trace_boundaries() {
+    let log = log_of_activities(&[&["a", "b", "c"], &["a", "c"], &["b"], &[]]);
+    assert_eq!(
+        log.traces[0].first_event().and_then(|event| event.name()),
+        Some("a")
+    );
+    assert_eq!(
+        log.traces[0].last_event().and_then(|event| event.name()),
+        Some("c")
+    );
+    assert!(log.traces[3].first_event().is_none());
+    assert_eq!(
+        log.start_activities(),
+        HashMap::from([("a".to_owned(), 2), ("b".to_owned(), 1)])
+    );
+    assert_eq!(
+        log.end_activities(),
+        HashMap::from([("c".to_owned(), 2), ("b".to_owned(), 1)])
+    );
+}
+
+#[test]
+fn strip_timezones_drops_offsets() {
+    let text = r#"<log version="2.0"><trace><event>
+        <date key="time:timestamp" value="2020-01-02T03:04:05+02:00"/>
+        <date key="naive" value="2020-01-02T03:04:05.250"/>
+    </event></trace></log>"#;
+    let stripped = |offset| {
+        let mut log = log(text);
+        log.strip_timezones(offset);
+        let attributes = log.traces[0].events[0].attributes.clone();
+        (
+            attributes["time:timestamp"].clone(),
+            attributes["naive"].clone(),
+        )
+    };
+    assert_eq!(
+        stripped(TimezoneOffset::ConvertToUtc),
+        (
+            Attribute::DateTime("2020-01-02T01:04:05".to_owned()),
+            Attribute::DateTime("2020-01-02T03:04:05.250".to_owned())
+        )
+    );
+    assert_eq!(
+        stripped(TimezoneOffset::Truncate).0,
+        Attribute::DateTime("2020-01-02T03:04:05".to_owned())
+    );
+}
+
+#[test]
+fn value_cardinality_counts_distinct_values() {
+    let log = log(&format!(
+        r#"<log version="2.0"><trace>{}{}{}{}</trace>{}</log>"#,
+        event(&[("org:resource", "ann"), ("concept:name", "a")]),
+        event(&[("org:resource", "bob"), ("concept:name", "a")]),
+        event(&[("org:resource", "ann")]),
+        event(&[("org:resource", "cid")]),
+        event(&[("concept:name", "b")]),
+    ));
+    assert_eq!(log.value_cardinality("org:resource"), 3);
+    assert_eq!(log.value_cardinality("missing"), 0);
+    assert_eq!(
+        log.attribute_profile(),
+        HashMap::from([
+            ("org:resource".to_owned(), 3),
+            ("concept:name".to_owned(), 2)
+        ])
+    );
+}
+
+#[test]
+fn map_attributes_rounds_doubles_at_every_level() {
+    let mut log = log(r#"<log version="2.0">
+        <float key="rate" value="0.12345"/>
+        <trace>
+            <float key="cost" value="10.006"/>
+            <event>
+                <float key="cost" value="1.234"/>
+                <list key="parts"><float key="cost" value="2.345678"/></list>
+                <string key="note" value="1.23456"/>
+            </event>
+        </trace>
+    </log>"#);
+    log.map_attributes(|_, value| {
+        if let Attribute::Double(value) = value {
+            *value = (*value * 100.0).round() / 100.0;
+        }
+    });
+    assert_eq!(log.attributes["rate"], Attribute::Double(0.12));
+    assert_eq!(log.traces[0].attributes["cost"], Attribute::Double(10.01));
+    let event = &log.traces[0].events[0];
+    assert_eq!(event.attributes["cost"], Attribute::Double(1.23));
+    assert_eq!(
+        event.attributes["parts"],
+        Attribute::list_from([("cost", Attribute::Double(2.35))])
+    );
+    assert_eq!(
+        event.attributes["note"],
+        Attribute::String("1.23456".to_owned())
+    );
+}
+
+#[test]
+fn self_loops_counts_repeated_activities() {
+    let log = log_of_activities(&[&["a", "b", "b", "b", "c"], &["c", "c"], &["a", "b"]]);
+    assert_eq!(
+        log.self_loops(),
+        HashMap::from([("b".to_owned(), 2), ("c".to_owned(), 1)])
+    );
+}
+
+#[test]
+fn any_and_all_events_have_a_key() {
+    let log = log(&format!(
+        r#"<log version="2.0"><trace>{}{}</trace>{}</log>"#,
+        event(&[("concept:name", "a"), ("org:resource", "ann")]),
+        event(&[("concept:name", "b")]),
+        event(&[("concept:name", "c")]),
+    ));
+    assert!(log.any_event_has("org:resource"));
+    assert!(!log.all_events_have("org:resource"));
+    assert!(log.all_events_have("concept:name"));
+    assert!(!log.any_event_has("time:timestamp"));
+}
+
+#[test]
+fn skeleton_keeps_only_control_flow() {
+    let log = log(&format!(
+        r#"<log version="2.0">
+        <extension name="Concept" prefix="concept" uri="http://www.xes-standard.org/concept.xesext"/>
+        <extension name="Lifecycle" prefix="lifecycle" uri="http://www.xes-standard.org/lifecycle.xesext"/>
+        <extension name="Organizational" prefix="org" uri="http://www.xes-standard.org/org.xesext"/>
+        <string key="source" value="erp"/>
+        <trace><string key="concept:name" value="case"/><string key="customer" value="c"/>{}{}</trace>
+        </log>"#,
+        event(&[
+            ("concept:name", "a"),
+            ("lifecycle:transition", "complete"),
+            ("org:resource", "ann"),
+        ]),
+        event(&[("concept:name", "b"), ("cost", "3")]),
+    ));
+    let skeleton = log.skeleton();
+    let prefixes: Vec<_> = skeleton
+        .extensions
+        .iter()
+        .map(|e| e.prefix.as_str())
+        .collect();
+    assert_eq!(prefixes, ["concept", "lifecycle"]);
+    assert!(skeleton.attributes.is_empty());
+    let trace = &skeleton.traces[0];
+    assert_eq!(
+        trace.attributes.keys().collect::<Vec<_>>(),
+        ["concept:name"]
+    );
+    let mut keys: Vec<_> = trace.events[0]
+        .attributes
+        .keys()
+        .map(|k| k.as_str())
+        .collect();
+    keys.sort();
+    assert_eq!(keys, ["concept:name", "lifecycle:transition"]);
+    assert_eq!(trace.events[1].attributes.len(), 1);
+    assert_eq!(trace.events[1].name(), Some("b"));
+}
+
+#[test]
+fn synthetic_log_is_reproducible_by_seed() {
+    assert_eq!(Log::synthetic(20, 8, 42), Log::synthetic(20, 8, 42));
+    assert_ne!(Log::synthetic(20, 8, 42), Log::synthetic(20, 8, 43));
+    let text = xes::write_string(&Log::synthetic(20, 8, 42));
+    assert_eq!(xes::read_str(&text).unwrap()[0], Log::synthetic(20, 8, 42));
+}
+
+#[test]
+fn synthetic_log_has_the_requested_shape() {
+    let log = Log::synthetic(10, 5, 1);
+    assert_eq!(log.traces.len(), 10);
+    assert!(log.traces.iter().all(|trace| trace.events.len() == 5));
+    assert_eq!(log.traces[3].name(), Some("case 3"));
+    let timestamps: Vec<_> = log.all_events().filter_map(|e| e.timestamp()).collect();
+    assert_eq!(timestamps.len(), 50);
+    assert!(timestamps.windows(2).all(|pair| pair[0] < pair[1]));
+    let activities = &log.traces[0].events;
+    let names: Vec<_> = activities.iter().filter_map(|e| e.name()).collect();
+    let first = (names[0].as_bytes()[0] - b'A') as usize;
+    let expected: Vec<_> = (0..5)
+        .map(|i| ["A", "B", "C", "D", "E"][(first + i) % 5])
+        .collect();
+    assert_eq!(names, expected);
+}
+
+#[test]
+fn incomplete_traces_lack_an_end_activity() {
+    let log = log_of_activities(&[&["a", "b", "end"], &["a", "b"], &["a", "cancel"], &[]]);
+    assert_eq!(log.incomplete_traces(&["end", "cancel"]), [1, 3]);
+    assert_eq!(log.incomplete_traces(&["end"]), [1, 2, 3]);
+}
+
+#[test]
+fn dot_start_and_end_markers_do_not_collide_with_activities() {
+    let log = log_of_activities(&[&["start", "work", "end"], &["start", "end"]]);
+    assert_eq!(
+        log.dfg_to_dot_with_start_end(1),
+        "digraph dfg {
+    __start [shape=circle, label=\"\"];
+    __end [shape=doublecircle, label=\"\"];
+    n0 [label=\"end\"];
+    n1 [label=\"start\"];
+    n2 [label=\"work\"];
+    n1 -> n0 [label=\"1\"];
+    n1 -> n2 [label=\"1\"];
+    n2 -> n0 [label=\"1\"];
+    __start -> n1 [label=\"2\"];
+    n0 -> __end [label=\"2\"];
+}
+"
+    );
+    assert!(!log.dfg_to_dot(2).contains("->"));
+}
+
+#[test]
+fn dot_markers_stay_distinct_from_activities_named_like_them() {
+    let log = log_of_activities(&[&["__start", "__end"]]);
+    assert_eq!(
+        log.dfg_to_dot_with_start_end(1),
+        "digraph dfg {
+    __start [shape=circle, label=\"\"];
+    __end [shape=doublecircle, label=\"\"];
+    n0 [label=\"__end\"];
+    n1 [label=\"__start\"];
+    n1 -> n0 [label=\"1\"];
+    __start -> n1 [label=\"1\"];
+    n0 -> __end [label=\"1\"];
+}
+"
+    );
+}
+
+#[test]
+fn log_name_reads_the_log_level_concept_name() {
+    let named = log(r#"<log version="2.0">
+        <string key="concept:name" value="BPI Challenge"/>
+        <trace><string key="concept:name" value="case"/></trace>
+    </log>"#);
+    assert_eq!(named.name(), Some("BPI Challenge"));
+    assert_eq!(log(r#"<log version="2.0"/>"#).name(), None);
+}
+
+#[test]
+fn filter_by_time_window_keeps_traces_started_that_day() {
+    let trace = |name: &str, times: &[&str]| {
+        let mut text = format!(r#"<trace><string key="concept:name" value="{name}"/>"#);
+        for time in times {
+            text.push_str(&format!(
+                r#"<event><date key="time:timestamp" value="{time}"/></event>"#
+            ));
+        }
+        text + "</trace>"
+    };
+    let log = log(&format!(
+        r#"<log version="2.0">{}{}{}{}{}</log>"#,
+        trace("before", &["2020-03-01T23:59:59Z", "2020-03-02T10:00:00Z"]),
+        trace("morning", &["2020-03-02T08:00:00Z", "2020-03-03T08:00:00Z"]),
+        trace("late", &["2020-03-03T00:30:00+01:00"]),
+        trace("next day", &["2020-03-03T00:00:00Z"]),
+        trace("untimed", &[]),
+    ));
+    let day = Utc.with_ymd_and_hms(2020, 3, 2, 0, 0, 0).unwrap();
+    let names = |log: &Log| -> Vec<String> {
+        log.traces
+            .iter()
+            .filter_map(|trace| trace.name().map(str::to_owned))
+            .collect()
+    };
+    let within = log.filter_by_time_window(day..day + Duration::days(1));
+    assert_eq!(names(&within), ["morning", "late"]);
+    let inclusive = log.filter_by_time_window(day..=day + Duration::days(1));
+    assert_eq!(names(&inclusive), ["morning", "late", "next day"]);
+}
+
+#[test]
+fn referenced_prefixes_cover_every_level() {
+    let log = log(r#"<log version="2.0">
+        <extension name="Organizational" prefix="org" uri="http://www.xes-standard.org/org.xesext"/>
+        <global scope="event"><string key="concept:name" value="?"/></global>
+        <string key="source" value="erp"/>
+        <trace>
+            <event>
+                <date key="time:timestamp" value="2020-01-01T00:00:00Z"/>
+                <list key="details"><string key="concept:instance" value="1"/></list>
+            </event>
+        </trace>
+    </log>"#);
+    assert_eq!(
+        log.referenced_prefixes(),
+        HashSet::from(["concept".to_owned(), "time".to_owned()])
+    );
+}
+
+#[test]
+fn prune_unused_extensions_keeps_used_ones() {
+    let mut log = log(r#"<log version="2.0">
+        <extension name="Concept" prefix="concept" uri="http://www.xes-standard.org/concept.xesext"/>
+        <extension name="Time" prefix="time" uri="http://www.xes-standard.org/time.xesext"/>
+        <extension name="Organizational" prefix="org" uri="http://www.xes-standard.org/org.xesext"/>
+        <trace><event>
+            <string key="concept:name" value="a"/>
+            <date key="time:timestamp" value="2020-01-01T00:00:00Z"/>
+        </event></trace>
+    </log>"#);
+    log.prune_unused_extensions();
+    let prefixes: Vec<_> = log.extensions.iter().map(|e| e.prefix.as_str()).collect();
+    assert_eq!(prefixes, ["concept", "time"]);
+}
+
+#[test]
+fn prune_unused_extensions_keeps_ones_only_classifiers_use() {
+    let mut log = log(r#"<log version="2.0">
+        <extension name="Lifecycle" prefix="lifecycle" uri="http://www.xes-standard.org/lifecycle.xesext"/>
+        <extension name="Organizational" prefix="org" uri="http://www.xes-standard.org/org.xesext"/>
+        <classifier name="Transition" keys="lifecycle:transition"/>
+        <trace><event><string key="concept:name" value="a"/></event></trace>
+    </log>"#);
+    log.prune_unused_extensions();
+    let prefixes: Vec<_> = log.extensions.iter().map(|e| e.prefix.as_str()).collect();
+    assert_eq!(prefixes, ["lifecycle"]);
+}
@@ -0,0 +1,128 @@
+//! Inputs that used to panic or overflow the stack, which must be rejected with an error.
+
+use xes::ReadOptions;
+use xes::XesError;
+
+fn read_error(text: &str) -> XesError {
+    xes::read_str(text).unwrap_err()
+}
+
+#[test]
+fn log_without_version() {
+    assert!(matches!(
+        read_error("<log/>"),
+        XesError::MissingAttribute {
+            attribute: "version",
+            ..
+        }
+    ));
+}
+
+#[test]
+fn attribute_without_key() {
+    assert!(matches!(
+        read_error(r#"<log version="2.0"><string value="a"/></log>"#),
+        XesError::MissingAttribute {
+            attribute: "key",
+            ..
+        }
+    ));
+}
+
+#[test]
+fn extension_without_uri() {
+    assert!(matches!(
+        read_error(r#"<log version="2.0"><extension name="Concept" prefix="concept"/></log>"#),
+        XesError::MissingAttribute {
+            attribute: "uri",
+            ..
+        }
+    ));
+}
+
+#[test]
+fn unparsable_values() {
+    for element in [
+        r#"<long key="a" value="one"/>"#,
+        r#"<double key="a" value="1,5"/>"#,
+        r#"<boolean key="a" value="yes"/>"#,
+    ] {
+        let text = format!(r#"<log version="2.0"><trace><event>{element}</event></trace></log>"#);
+        assert!(
+            matches!(read_error(&text), XesError::InvalidValue { .. }),
+            "{element}"
+        );
+    }
+}
+
+#[test]
+fn unclosed_element() {
+    assert!(matches!(
+        read_error(r#"<log version="2.0"><trace>"#),
+        XesError::Xml(_)
+    ));
+}
+
+#[test]
+fn deeply_nested_lists() {
+    let depth = 5_000;
+    let text = format!(
+        r#"<log version="2.0">{}{}</log>"#,
+        r#"<list key="a">"#.repeat(depth),
+        "</list>".repeat(depth)
+    );
+    assert!(matches!(read_error(&text), XesError::NestingTooDeep(_)));
+}
+
+#[test]
+fn deeply_nested_logs() {
+    let depth = 5_000;
+    let text = format!(
+        "{}{}",
+        r#"<log version="2.0">"#.repeat(depth),
+        "</log>".repeat(depth)
+    );
+    assert!(matches!(read_error(&text), XesError::NestingTooDeep(_)));
+}
+
+#[test]
+fn deeply_nested_wrapper() {
+    let depth = 5_000;
+    let text = format!(
+        r#"{}<log version="2.0"/>{}"#,
+        "<a>".repeat(depth),
+        "</a>".repeat(depth)
+    );
+    let options = ReadOptions {
+        deep_search: true,
+        ..ReadOptions::default()
+    };
+    assert_eq!(
+        xes::read_str_with_options(&text, &options).unwrap().len(),
+        1
+    );
+}
+
+#[test]
+fn deeply_nested_lists_while_streaming() {
+    let depth = 5_000;
+    let text = format!(
+        r#"<log version="2.0"><trace><event>{}{}</event></trace></log>"#,
+        r#"<list key="a">"#.repeat(depth),
+        "</list>".repeat(depth)
+    );
+    let result = xes::transform_stream(text.as_bytes(), Vec::new(), |_| true);
+    assert!(matches!(result, Err(XesError::NestingTooDeep(_))));
+}
+
+#[test]
+fn lists_nested_up_to_the_limit() {
+    let depth = 256;
+    let text = format!(
+        r#"<log version="2.0">{}{}</log>"#,
+        r#"<list key="a">"#.repeat(depth),
+        "</list>".repeat(depth)
+    );
+    let log = xes::read_str(&text).unwrap().remove(0);
+    assert_eq!(xes::read_str(&xes::write_string(&log)).unwrap(), [log]);
+}
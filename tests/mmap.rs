@@ -0,0 +1,24 @@
+#![cfg(feature = "mmap")]
+
+use std::path::PathBuf;
+use xes::XesError;
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/inputs/example1.xml");
+
+#[test]
+fn mapped_read_matches_read() {
+    assert_eq!(
+        xes::read_mmap(FIXTURE).unwrap(),
+        xes::read(FIXTURE).unwrap()
+    );
+}
+
+#[test]
+fn mapped_read_rejects_empty_and_invalid_files() {
+    let empty = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("empty.xes");
+    std::fs::write(&empty, "").unwrap();
+    assert!(matches!(xes::read_mmap(&empty), Err(XesError::NoLogs)));
+    let invalid = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("invalid-utf8.xes");
+    std::fs::write(&invalid, b"<log version=\"2.0\">\xff</log>").unwrap();
+    assert!(matches!(xes::read_mmap(&invalid), Err(XesError::Io(_))));
+}
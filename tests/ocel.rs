@@ -0,0 +1,47 @@
+#![cfg(feature = "ocel")]
+
+use serde_json::json;
+
+#[test]
+fn ocel_json_has_an_event_per_activity() {
+    let log = xes::read_str(
+        r#"<log version="2.0">
+        <trace>
+            <string key="concept:name" value="order-1"/>
+            <event>
+                <string key="concept:name" value="create"/>
+                <date key="time:timestamp" value="2020-01-01T00:00:00Z"/>
+            </event>
+            <event><string key="org:resource" value="unnamed"/></event>
+        </trace>
+        <trace><event><string key="concept:name" value="pay"/></event></trace>
+    </log>"#,
+    )
+    .unwrap()
+    .remove(0);
+    let ocel = log.to_ocel_json();
+    assert_eq!(
+        ocel["events"],
+        json!([
+            {
+                "id": "e1",
+                "type": "create",
+                "time": "2020-01-01T00:00:00+00:00",
+                "attributes": [],
+                "relationships": [{ "objectId": "order-1", "qualifier": "case" }],
+            },
+            {
+                "id": "e2",
+                "type": "pay",
+                "time": "",
+                "attributes": [],
+                "relationships": [{ "objectId": "case-1", "qualifier": "case" }],
+            },
+        ])
+    );
+    assert_eq!(ocel["objects"].as_array().unwrap().len(), 2);
+    assert_eq!(
+        ocel["eventTypes"],
+        json!([{ "name": "create", "attributes": [] }, { "name": "pay", "attributes": [] }])
+    );
+}
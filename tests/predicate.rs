@@ -0,0 +1,24 @@
+use xes::Attribute;
+use xes::Operator;
+use xes::Predicate;
+
+#[test]
+fn filter_keeps_events_with_large_enough_cost() {
+    let mut log = xes::read_str(
+        r#"<log version="2.0">
+        <trace>
+            <event><string key="concept:name" value="a"/><long key="cost:amount" value="50"/></event>
+            <event><string key="concept:name" value="b"/><long key="cost:amount" value="100"/></event>
+            <event><string key="concept:name" value="c"/><double key="cost:amount" value="250.5"/></event>
+            <event><string key="concept:name" value="d"/></event>
+        </trace>
+        <event><string key="concept:name" value="e"/><string key="cost:amount" value="999"/></event>
+    </log>"#,
+    )
+    .unwrap()
+    .remove(0);
+    let predicate = Predicate::new("cost:amount", Operator::Ge, Attribute::Long(100));
+    log.filter_events(&predicate);
+    assert_eq!(log.traces[0].activities(), ["b", "c"]);
+    assert!(log.events.is_empty());
+}
@@ -0,0 +1,343 @@
+use xes::Attribute;
+use xes::ReadOptions;
+use xes::XesError;
+
+fn strict() -> ReadOptions {
+    ReadOptions {
+        strict: true,
+        ..ReadOptions::default()
+    }
+}
+
+#[test]
+fn repeated_keys_keep_their_values() {
+    let text = r#"<log version="2.0"><trace>
+        <event><string key="concept:name" value="a"/><long key="n" value="1"/></event>
+        <event><string key="concept:name" value="b"/><long key="n" value="2"/></event>
+    </trace></log>"#;
+    let log = xes::read_str(text).unwrap().remove(0);
+    let values: Vec<_> = log.traces[0]
+        .events
+        .iter()
+        .map(|event| (&event.attributes["concept:name"], &event.attributes["n"]))
+        .collect();
+    assert_eq!(
+        values,
+        [
+            (&Attribute::String("a".to_owned()), &Attribute::Long(1)),
+            (&Attribute::String("b".to_owned()), &Attribute::Long(2)),
+        ]
+    );
+}
+
+#[test]
+fn versions_load_and_strict_mode_rejects_garbage() {
+    for version in ["1.0", "2.0"] {
+        let text = format!(r#"<log version="{version}"/>"#);
+        let log = xes::read_str_with_options(&text, &strict())
+            .unwrap()
+            .remove(0);
+        assert_eq!(log.version, version);
+    }
+    let garbage = r#"<log version="garbage"/>"#;
+    assert_eq!(xes::read_str(garbage).unwrap()[0].version, "garbage");
+    assert!(matches!(
+        xes::read_str_with_options(garbage, &strict()),
+        Err(XesError::UnsupportedVersion(version)) if version == "garbage"
+    ));
+}
+
+#[test]
+fn repeated_extension_declaration_collapses_to_one() {
+    let concept = r#"<extension name="Concept" prefix="concept" uri="http://www.xes-standard.org/concept.xesext"/>"#;
+    let text = format!(r#"<log version="2.0">{concept}{concept}</log>"#);
+    let log = xes::read_str(&text).unwrap().remove(0);
+    assert_eq!(log.extensions.len(), 1);
+    assert_eq!(log.extensions[0].prefix, "concept");
+    assert!(matches!(
+        xes::read_str_with_options(&text, &strict()),
+        Err(XesError::DuplicateExtension(_))
+    ));
+}
+
+#[test]
+fn extensions_sharing_a_uri_under_different_prefixes_are_kept() {
+    let logs = xes::read(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/examples/inputs/example1.xml"
+    ))
+    .unwrap();
+    let prefixes: Vec<_> = logs[0]
+        .extensions
+        .iter()
+        .map(|extension| extension.prefix.as_str())
+        .collect();
+    assert_eq!(prefixes, ["ex1", "ex2", "ex3"]);
+}
+
+#[test]
+fn container_is_read_as_a_list() {
+    let text = r#"<log version="2.0"><trace><event>
+        <container key="cost:drivers">
+            <string key="cost:type" value="labour"/>
+            <container key="inner"><long key="amount" value="5"/></container>
+        </container>
+    </event></trace></log>"#;
+    let log = xes::read_str(text).unwrap().remove(0);
+    let expected = Attribute::list_from([
+        ("cost:type", Attribute::String("labour".to_owned())),
+        (
+            "inner",
+            Attribute::list_from([("amount", Attribute::Long(5))]),
+        ),
+    ]);
+    assert_eq!(log.traces[0].events[0].attributes["cost:drivers"], expected);
+}
+
+#[test]
+fn read_dir_reports_each_file() {
+    let directory = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("read_dir");
+    let _ = std::fs::remove_dir_all(&directory);
+    std::fs::create_dir_all(&directory).unwrap();
+    std::fs::write(
+        directory.join("a.xes"),
+        r#"<log version="2.0"><trace/></log>"#,
+    )
+    .unwrap();
+    std::fs::write(directory.join("b.xes"), "<log").unwrap();
+    std::fs::write(directory.join("c.xes"), r#"<log version="1.0"/>"#).unwrap();
+    std::fs::write(directory.join("notes.txt"), "not a log").unwrap();
+    let results = xes::read_dir(&directory).unwrap();
+    let names: Vec<_> = results
+        .iter()
+        .map(|(path, _)| path.file_name().unwrap().to_str().unwrap())
+        .collect();
+    assert_eq!(names, ["a.xes", "b.xes", "c.xes"]);
+    assert_eq!(results[0].1.as_ref().unwrap()[0].traces.len(), 1);
+    assert!(matches!(results[1].1, Err(XesError::Xml(_))));
+    assert_eq!(results[2].1.as_ref().unwrap()[0].version, "1.0");
+}
+
+#[test]
+fn nested_log_is_read_as_a_sub_log() {
+    let text = r#"<log version="2.0">
+        <string key="concept:name" value="outer"/>
+        <trace/>
+        <log version="1.0">
+            <string key="concept:name" value="inner"/>
+            <trace><event/></trace>
+        </log>
+    </log>"#;
+    let logs = xes::read_str(text).unwrap();
+    assert_eq!(logs.len(), 1);
+    let outer = &logs[0];
+    assert_eq!(outer.name(), Some("outer"));
+    assert_eq!(outer.traces.len(), 1);
+    assert_eq!(outer.sub_logs.len(), 1);
+    let inner = &outer.sub_logs[0];
+    assert_eq!(inner.name(), Some("inner"));
+    assert_eq!(inner.version, "1.0");
+    assert_eq!(inner.traces[0].events.len(), 1);
+    let reread = xes::read_str(&xes::write_string(outer)).unwrap();
+    assert_eq!(&reread[0], outer);
+}
+
+#[test]
+fn lowercase_keys_normalizes_mixed_case() {
+    let text = r#"<log version="2.0"><trace><event>
+        <string key="Concept:Name" value="A"/>
+        <list key="Cost:Drivers"><long key="AMOUNT" value="1"/></list>
+    </event></trace></log>"#;
+    let options = ReadOptions {
+        lowercase_keys: true,
+        ..ReadOptions::default()
+    };
+    let log = xes::read_str_with_options(text, &options)
+        .unwrap()
+        .remove(0);
+    let event = &log.traces[0].events[0];
+    assert_eq!(event.name(), Some("A"));
+    assert_eq!(
+        event.attributes["cost:drivers"],
+        Attribute::list_from([("amount", Attribute::Long(1))])
+    );
+    let log = xes::read_str(text).unwrap().remove(0);
+    assert!(log.traces[0].events[0]
+        .attributes
+        .contains_key("Concept:Name"));
+}
+
+#[test]
+fn skip_errors_drops_only_the_bad_event() {
+    let text = r#"<log version="2.0"><trace>
+        <event><string key="concept:name" value="a"/></event>
+        <event><long key="cost:amount" value="lots"/></event>
+        <event><string key="concept:name" value="c"/></event>
+    </trace></log>"#;
+    assert!(matches!(
+        xes::read_str(text),
+        Err(XesError::InvalidValue { .. })
+    ));
+    let options = ReadOptions {
+        skip_errors: true,
+        ..ReadOptions::default()
+    };
+    let (logs, diagnostics) = xes::read_str_with_diagnostics(text, &options).unwrap();
+    assert_eq!(logs[0].traces[0].activities(), ["a", "c"]);
+    assert_eq!(diagnostics.len(), 1);
+    assert!(matches!(
+        &diagnostics[0],
+        XesError::InvalidValue { key, value } if key == "cost:amount" && value == "lots"
+    ));
+}
+
+#[test]
+fn out_of_range_long_is_rejected() {
+    let text = r#"<log version="2.0"><trace><event>
+        <long key="big" value="9223372036854775808"/>
+    </event></trace></log>"#;
+    assert!(matches!(
+        xes::read_str(text),
+        Err(XesError::LongOutOfRange { key, value })
+            if key == "big" && value == "9223372036854775808"
+    ));
+    let text = r#"<log version="2.0"><long key="min" value="-9223372036854775808"/></log>"#;
+    let log = xes::read_str(text).unwrap().remove(0);
+    assert_eq!(log.attributes["min"], Attribute::Long(i64::MIN));
+}
+
+#[test]
+fn empty_input_and_documents_without_logs_are_rejected() {
+    for text in ["", " \n\t ", "<root><trace/></root>"] {
+        assert!(
+            matches!(xes::read_str(text), Err(XesError::NoLogs)),
+            "{text:?}"
+        );
+    }
+}
+
+#[test]
+fn children_in_any_order_read_the_same() {
+    let ordered = r#"<log version="2.0">
+        <extension name="Concept" prefix="concept" uri="http://www.xes-standard.org/concept.xesext"/>
+        <global scope="event"><string key="concept:name" value="?"/></global>
+        <classifier name="activity" keys="concept:name"/>
+        <string key="concept:name" value="log"/>
+        <trace>
+            <string key="concept:name" value="t"/>
+            <event><string key="concept:name" value="a"/></event>
+            <event><string key="concept:name" value="b"/></event>
+        </trace>
+        <event><string key="concept:name" value="loose"/></event>
+    </log>"#;
+    let mixed = r#"<log version="2.0">
+        <event><string key="concept:name" value="loose"/></event>
+        <trace>
+            <event><string key="concept:name" value="a"/></event>
+            <string key="concept:name" value="t"/>
+            <event><string key="concept:name" value="b"/></event>
+        </trace>
+        <string key="concept:name" value="log"/>
+        <classifier name="activity" keys="concept:name"/>
+        <global scope="event"><string key="concept:name" value="?"/></global>
+        <extension name="Concept" prefix="concept" uri="http://www.xes-standard.org/concept.xesext"/>
+    </log>"#;
+    let ordered = xes::read_str(ordered).unwrap();
+    let mixed = xes::read_str(mixed).unwrap();
+    assert_eq!(mixed, ordered);
+    assert_eq!(xes::write_string(&mixed[0]), xes::write_string(&ordered[0]));
+}
+
+#[test]
+fn deep_search_finds_logs_nested_in_a_wrapper() {
+    let text = r#"<export>
+        <batch>
+            <log version="2.0"><string key="concept:name" value="first"/></log>
+        </batch>
+        <log version="2.0"><string key="concept:name" value="second"/></log>
+    </export>"#;
+    assert!(matches!(xes::read_str(text), Err(XesError::NoLogs)));
+    let options = ReadOptions {
+        deep_search: true,
+        ..ReadOptions::default()
+    };
+    let logs = xes::read_str_with_options(text, &options).unwrap();
+    let names: Vec<_> = logs.iter().map(|log| log.name()).collect();
+    assert_eq!(names, [Some("first"), Some("second")]);
+}
+
+#[test]
+fn trailing_blank_lines_are_ignored() {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("trailing.xes");
+    std::fs::write(&path, "\n<log version=\"2.0\"><trace/></log>\n\n  \n\n").unwrap();
+    assert_eq!(xes::read(&path).unwrap()[0].traces.len(), 1);
+    let mut traces = 0;
+    xes::read_traces(&path, |_, _| traces += 1).unwrap();
+    assert_eq!(traces, 1);
+}
+
+#[test]
+fn ranges_span_each_log_element() {
+    let first = r#"<log version="2.0"><trace><event/></trace></log>"#;
+    let second = r#"<log version="1.0"/>"#;
+    let text = format!("<logs>\n  {first}\n  {second}\n</logs>\n");
+    let options = ReadOptions {
+        deep_search: true,
+        ..ReadOptions::default()
+    };
+    let logs = xes::read_str_with_ranges(&text, &options).unwrap();
+    let spans: Vec<_> = logs.iter().map(|(range, _)| &text[range.clone()]).collect();
+    assert_eq!(spans, [first, second]);
+    assert_eq!(logs[1].1.version, "1.0");
+}
+
+#[test]
+fn canonicalize_values_normalizes_renderings() {
+    let text = r#"<log version="2.0"><trace><event>
+        <boolean key="done" value="TRUE"/>
+        <float key="cost" value="1.50"/>
+        <date key="time:timestamp" value="2020-01-01T12:00:00Z"/>
+    </event></trace></log>"#;
+    assert!(xes::read_str(text).is_err());
+    let options = ReadOptions {
+        canonicalize_values: true,
+        ..ReadOptions::default()
+    };
+    let log = xes::read_str_with_options(text, &options)
+        .unwrap()
+        .remove(0);
+    let event = &log.traces[0].events[0];
+    assert_eq!(event.attributes["done"], Attribute::Boolean(true));
+    assert_eq!(
+        event.attributes["time:timestamp"],
+        Attribute::DateTime("2020-01-01T12:00:00+00:00".to_owned())
+    );
+    let written = xes::write_string(&log);
+    assert!(written.contains(r#"value="true""#));
+    assert!(written.contains(r#"value="1.5""#));
+    assert!(written.contains(r#"value="2020-01-01T12:00:00+00:00""#));
+}
+
+#[test]
+fn read_single_requires_exactly_one_log() {
+    let file = |name: &str, content: &str| {
+        let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    };
+    let one = file(
+        "single.xes",
+        r#"<export><log version="2.0"><trace/></log></export>"#,
+    );
+    assert_eq!(xes::read_single(one).unwrap().traces.len(), 1);
+    let none = file("single-none.xes", "<export/>");
+    assert!(matches!(xes::read_single(none), Err(XesError::NoLogs)));
+    let two = file(
+        "single-two.xes",
+        r#"<export><log version="2.0"/><log version="2.0"/></export>"#,
+    );
+    assert!(matches!(
+        xes::read_single(two),
+        Err(XesError::MultipleLogs(2))
+    ));
+}
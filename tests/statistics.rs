@@ -0,0 +1,76 @@
+use chrono::Duration;
+use std::collections::BTreeMap;
+use xes::Log;
+use xes::NumericSummary;
+use xes::OrderedFloat;
+
+fn log(text: &str) -> Log {
+    xes::read_str(text).unwrap().remove(0)
+}
+
+#[test]
+fn numeric_summary_of_cost_amounts() {
+    let log = log(r#"<log version="2.0"><trace>
+        <event><long key="cost:amount" value="10"/></event>
+        <event><double key="cost:amount" value="30.5"/></event>
+        <event><string key="cost:amount" value="free"/></event>
+        <event><long key="cost:amount" value="-4"/></event>
+    </trace></log>"#);
+    assert_eq!(
+        log.numeric_summary("cost:amount"),
+        Some(NumericSummary {
+            count: 3,
+            sum: 36.5,
+            min: -4.0,
+            max: 30.5,
+            mean: 36.5 / 3.0,
+        })
+    );
+    assert_eq!(log.numeric_summary("cost:total"), None);
+}
+
+/// Log with a trace per entry of `hours`, each lasting that many hours.
+fn log_of_durations(hours: &[i64]) -> Log {
+    let mut text = String::from(r#"<log version="2.0">"#);
+    for hours in hours {
+        text.push_str(&format!(
+            r#"<trace>
+                <event><date key="time:timestamp" value="2020-01-01T00:00:00Z"/></event>
+                <event><date key="time:timestamp" value="2020-01-01T{hours:02}:00:00Z"/></event>
+            </trace>"#
+        ));
+    }
+    text.push_str("<trace><event/></trace></log>");
+    log(&text)
+}
+
+#[test]
+fn cycle_time_percentiles_interpolate_between_durations() {
+    let log = log_of_durations(&[4, 1, 2]);
+    let percentiles = log.cycle_time_percentiles(&[0.0, 25.0, 50.0, 100.0]);
+    assert_eq!(
+        percentiles,
+        BTreeMap::from([
+            (OrderedFloat(0.0), Duration::hours(1)),
+            (OrderedFloat(25.0), Duration::minutes(90)),
+            (OrderedFloat(50.0), Duration::hours(2)),
+            (OrderedFloat(100.0), Duration::hours(4)),
+        ])
+    );
+    assert!(Log::minimal().cycle_time_percentiles(&[50.0]).is_empty());
+}
+
+#[test]
+fn trace_length_stats_of_known_lengths() {
+    let mut text = String::from(r#"<log version="2.0">"#);
+    for length in [3, 1, 6, 2] {
+        text.push_str(&format!("<trace>{}</trace>", "<event/>".repeat(length)));
+    }
+    text.push_str("</log>");
+    let stats = log(&text).trace_length_stats().unwrap();
+    assert_eq!((stats.min, stats.max), (1, 6));
+    assert_eq!(stats.mean, 3.0);
+    assert_eq!(stats.median, 2.5);
+    assert!((stats.std_dev - 3.5f64.sqrt()).abs() < 1e-12);
+    assert_eq!(log(r#"<log version="2.0"/>"#).trace_length_stats(), None);
+}
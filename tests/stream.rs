@@ -0,0 +1,122 @@
+use std::path::PathBuf;
+use xes::Log;
+use xes::LogHeader;
+use xes::StandardExtension;
+
+/// Path of a scratch file called `name`.
+fn scratch_file(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name)
+}
+
+#[test]
+fn peek_header_reads_only_the_header() {
+    let mut log = Log::synthetic(2_000, 10, 1);
+    log.features = vec!["nested-attributes".to_owned()];
+    let path = scratch_file("peek_header.xes");
+    // The body is cut short, so reading more than the header would fail.
+    let text = xes::write_string(&log);
+    std::fs::write(&path, &text[..text.len() / 2]).unwrap();
+    assert!(xes::read(&path).is_err());
+    assert_eq!(
+        xes::peek_header(&path).unwrap(),
+        LogHeader {
+            version: "2.0".to_owned(),
+            features: vec!["nested-attributes".to_owned()],
+            extensions: vec![
+                StandardExtension::Concept.extension(),
+                StandardExtension::Time.extension(),
+            ],
+        }
+    );
+}
+
+#[test]
+fn read_traces_passes_every_trace_to_the_callback() {
+    let path = scratch_file("read_traces.xes");
+    std::fs::write(
+        &path,
+        r#"<log version="2.0">
+        <extension name="Concept" prefix="concept" uri="http://www.xes-standard.org/concept.xesext"/>
+        <trace><string key="concept:name" value="1"/><event/><event/></trace>
+        <event/>
+        <trace/>
+        <trace><event><list key="l"><string key="x" value="y"/></list></event></trace>
+    </log>"#,
+    )
+    .unwrap();
+    let mut counts = Vec::new();
+    let header = xes::read_traces(&path, |header, trace| {
+        assert_eq!(header.extensions.len(), 1);
+        counts.push((trace.name().map(str::to_owned), trace.events.len()));
+    })
+    .unwrap();
+    assert_eq!(header.version, "2.0");
+    assert_eq!(counts, [(Some("1".to_owned()), 2), (None, 0), (None, 1)]);
+}
+
+#[test]
+fn transform_stream_drops_and_changes_events() {
+    let input = r#"<log version="2.0">
+        <string key="concept:name" value="log"/>
+        <trace>
+            <event><string key="concept:name" value="a"/><string key="org:resource" value="ann"/></event>
+            <event><string key="concept:name" value="b"/></event>
+            <event><string key="concept:name" value="a"/></event>
+        </trace>
+        <event><string key="concept:name" value="b"/></event>
+    </log>"#;
+    let mut output = Vec::new();
+    xes::transform_stream(input.as_bytes(), &mut output, |event| {
+        event.attributes.remove("org:resource");
+        event.name() != Some("b")
+    })
+    .unwrap();
+    let log: Log = xes::read_str(std::str::from_utf8(&output).unwrap())
+        .unwrap()
+        .remove(0);
+    assert_eq!(log.name(), Some("log"));
+    let names: Vec<_> = log.traces[0].events.iter().map(|e| e.name()).collect();
+    assert_eq!(names, [Some("a"), Some("a")]);
+    assert!(!log.traces[0].events[0]
+        .attributes
+        .contains_key("org:resource"));
+    assert!(log.events.is_empty());
+}
+
+#[test]
+fn read_nth_reads_only_the_wanted_log() {
+    let path = scratch_file("read_nth.xes");
+    std::fs::write(
+        &path,
+        r#"<logs>
+        <log version="2.0"><string key="concept:name" value="first"/></log>
+        <log version="2.0"><string key="concept:name" value="second"/><trace/></log>
+    </logs>"#,
+    )
+    .unwrap();
+    let second = xes::read_nth(&path, 1).unwrap().unwrap();
+    assert_eq!(second.name(), Some("second"));
+    assert_eq!(second.traces.len(), 1);
+    assert_eq!(
+        xes::read_nth(&path, 0).unwrap().unwrap().name(),
+        Some("first")
+    );
+    assert!(xes::read_nth(&path, 2).unwrap().is_none());
+}
+
+#[test]
+fn read_nth_keeps_namespaces_declared_around_the_log() {
+    let path = scratch_file("read_nth_namespaces.xes");
+    std::fs::write(
+        &path,
+        r#"<root xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+        <log version="1.0" xsi:schemaLocation="x"/>
+        <group xmlns:xsi="http://example.com/&quot;other&quot;">
+            <log version="2.0" xsi:schemaLocation="y"/>
+        </group>
+    </root>"#,
+    )
+    .unwrap();
+    assert_eq!(xes::read_nth(&path, 0).unwrap().unwrap().version, "1.0");
+    assert_eq!(xes::read_nth(&path, 1).unwrap().unwrap().version, "2.0");
+}
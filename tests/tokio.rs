@@ -0,0 +1,12 @@
+#![cfg(feature = "tokio")]
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/inputs/example1.xml");
+
+#[test]
+fn read_async_reads_fixture() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let logs = runtime.block_on(xes::read_async(FIXTURE)).unwrap();
+    assert_eq!(logs, xes::read(FIXTURE).unwrap());
+}
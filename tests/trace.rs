@@ -0,0 +1,262 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::hash::Hasher;
+use xes::Attribute;
+use xes::Trace;
+use xes::VariantKey;
+
+/// Trace whose content is `content`.
+fn trace(content: &str) -> Trace {
+    let text = format!(r#"<log version="2.0"><trace>{content}</trace></log>"#);
+    xes::read_str(&text).unwrap().remove(0).traces.remove(0)
+}
+
+#[test]
+fn elapsed_times_count_from_the_first_timestamp() {
+    let trace = trace(
+        r#"
+        <event><date key="time:timestamp" value="2020-01-01T00:00:00Z"/></event>
+        <event><string key="concept:name" value="untimed"/></event>
+        <event><date key="time:timestamp" value="2020-01-01T00:01:30Z"/></event>
+        <event><date key="time:timestamp" value="2020-01-01T02:00:00+01:00"/></event>
+        "#,
+    );
+    assert_eq!(
+        trace.elapsed_times(),
+        [Some(0.0), None, Some(90.0), Some(3600.0)]
+    );
+}
+
+#[test]
+fn broadcast_copies_a_case_attribute_to_events_lacking_it() {
+    let mut trace = trace(
+        r#"
+        <string key="case:priority" value="high"/>
+        <event><string key="concept:name" value="a"/></event>
+        <event><string key="case:priority" value="low"/></event>
+        "#,
+    );
+    trace.broadcast_attribute("case:priority");
+    let priorities: Vec<_> = trace
+        .events
+        .iter()
+        .map(|event| event.attributes["case:priority"].as_str())
+        .collect();
+    assert_eq!(priorities, [Some("high"), Some("low")]);
+}
+
+#[test]
+fn truncating_keeps_the_first_events() {
+    let mut trace = trace(
+        r#"
+        <event><string key="concept:name" value="a"/></event>
+        <event><string key="concept:name" value="b"/></event>
+        <event><string key="concept:name" value="c"/></event>
+        <event><string key="concept:name" value="d"/></event>
+        <event><string key="concept:name" value="e"/></event>
+        "#,
+    );
+    let mut tail = trace.clone();
+    trace.truncate_events(3);
+    assert_eq!(trace.activities(), ["a", "b", "c"]);
+    tail.keep_last_events(3);
+    assert_eq!(tail.activities(), ["c", "d", "e"]);
+    trace.truncate_events(10);
+    assert_eq!(trace.events.len(), 3);
+}
+
+#[test]
+fn variant_key_groups_traces_in_a_map() {
+    let traces = [
+        trace(r#"<event><string key="concept:name" value="a"/></event>"#),
+        trace(r#"<event><string key="concept:name" value="b"/></event>"#),
+        trace(r#"<event><string key="concept:name" value="a"/></event>"#),
+    ];
+    let mut groups: HashMap<VariantKey, Vec<usize>> = HashMap::new();
+    for (index, trace) in traces.iter().enumerate() {
+        groups.entry(trace.variant_key()).or_default().push(index);
+    }
+    assert_eq!(groups[&VariantKey(vec!["a".to_owned()])], [0, 2]);
+    assert_eq!(groups[&VariantKey(vec!["b".to_owned()])], [1]);
+}
+
+#[test]
+fn equal_attributes_hash_equally() {
+    let hash = |attribute: &Attribute| {
+        let mut hasher = DefaultHasher::new();
+        attribute.hash(&mut hasher);
+        hasher.finish()
+    };
+    assert_eq!(
+        hash(&Attribute::Double(0.0)),
+        hash(&Attribute::Double(-0.0))
+    );
+    let list = |first: i64, second: i64| {
+        Attribute::list_from([
+            ("x", Attribute::Long(first)),
+            ("y", Attribute::Long(second)),
+        ])
+    };
+    assert_eq!(hash(&list(1, 2)), hash(&list(1, 2)));
+    assert_ne!(hash(&Attribute::Long(1)), hash(&Attribute::Double(1.0)));
+}
+
+#[test]
+fn next_activities_label_each_event_with_its_successor() {
+    let labelled = trace(
+        r#"
+        <event><string key="concept:name" value="a"/></event>
+        <event><string key="concept:name" value="b"/></event>
+        <event><string key="concept:name" value="c"/></event>
+        "#,
+    );
+    assert_eq!(labelled.next_activities(), [Some("b"), Some("c"), None]);
+    assert!(trace("").next_activities().is_empty());
+}
+
+#[test]
+fn clone_with_drops_and_maps_event_attributes() {
+    let original = trace(
+        r#"
+        <string key="concept:name" value="case"/>
+        <event><string key="concept:name" value="a"/><string key="org:resource" value="ann"/></event>
+        <event><long key="cost" value="3"/></event>
+        "#,
+    );
+    let anonymized = original.clone_with(|key, value| match (key, value) {
+        ("org:resource", _) => None,
+        (_, Attribute::Long(cost)) => Some(Attribute::Long(cost * 2)),
+        _ => Some(value.clone()),
+    });
+    assert_eq!(anonymized.attributes, original.attributes);
+    assert_eq!(anonymized.events.len(), 2);
+    assert_eq!(anonymized.events[0].attributes.len(), 1);
+    assert_eq!(anonymized.events[0].name(), Some("a"));
+    assert_eq!(anonymized.events[1].attributes["cost"], Attribute::Long(6));
+    assert!(original.events[0].attributes.contains_key("org:resource"));
+}
+
+#[test]
+fn overlapping_activities_reports_concurrent_intervals() {
+    let event = |name: &str, transition: &str, time: &str| {
+        format!(
+            r#"<event><string key="concept:name" value="{name}"/><string key="lifecycle:transition" value="{transition}"/><date key="time:timestamp" value="2020-01-01T{time}Z"/></event>"#
+        )
+    };
+    let trace = trace(
+        &[
+            event("a", "start", "10:00:00"),
+            event("b", "start", "10:30:00"),
+            event("a", "complete", "11:00:00"),
+            event("b", "complete", "11:30:00"),
+            event("c", "start", "11:30:00"),
+            event("c", "complete", "12:00:00"),
+        ]
+        .concat(),
+    );
+    assert_eq!(
+        trace.overlapping_activities(),
+        [("a".to_owned(), "b".to_owned())]
+    );
+}
+
+#[test]
+fn sort_events_by_attribute_orders_by_sequence_number() {
+    let mut sorted = trace(
+        r#"
+        <event><string key="concept:name" value="c"/><long key="sequence" value="3"/></event>
+        <event><string key="concept:name" value="a"/><long key="sequence" value="1"/></event>
+        <event><string key="concept:name" value="unnumbered"/></event>
+        <event><string key="concept:name" value="b"/><double key="sequence" value="2.0"/></event>
+        "#,
+    );
+    sorted.sort_events_by_attribute("sequence");
+    let names: Vec<_> = sorted.events.iter().map(|event| event.name()).collect();
+    assert_eq!(names, [Some("a"), Some("b"), Some("c"), Some("unnumbered")]);
+}
+
+#[test]
+fn start_and_end_time_ignore_document_order() {
+    let unordered = trace(
+        r#"
+        <event><date key="time:timestamp" value="2020-01-01T12:00:00Z"/></event>
+        <event><date key="time:timestamp" value="2020-01-01T10:00:00Z"/></event>
+        <event/>
+        <event><date key="time:timestamp" value="2020-01-01T11:00:00Z"/></event>
+        "#,
+    );
+    let at =
+        |hour| chrono::DateTime::parse_from_rfc3339(&format!("2020-01-01T{hour}:00:00Z")).unwrap();
+    assert_eq!(unordered.start_time(), Some(at(10)));
+    assert_eq!(unordered.end_time(), Some(at(12)));
+    assert_eq!(unordered.duration(), Some(chrono::Duration::hours(2)));
+}
+
+#[test]
+fn activity_timings_split_waiting_and_service_time() {
+    let event = |name: &str, transition: &str, time: &str| {
+        format!(
+            r#"<event><string key="concept:name" value="{name}"/><string key="lifecycle:transition" value="{transition}"/><date key="time:timestamp" value="2020-01-01T{time}Z"/></event>"#
+        )
+    };
+    let timed = trace(
+        &[
+            event("a", "schedule", "09:00:00"),
+            event("a", "start", "09:15:00"),
+            event("a", "complete", "10:00:00"),
+            event("b", "start", "10:30:00"),
+        ]
+        .concat(),
+    );
+    let timings = timed.activity_timings();
+    assert_eq!(timings.len(), 2);
+    assert_eq!(timings[0].activity, "a");
+    assert!(timings[0].is_complete());
+    assert_eq!(
+        timings[0].waiting_time(),
+        Some(chrono::Duration::minutes(15))
+    );
+    assert_eq!(
+        timings[0].service_time(),
+        Some(chrono::Duration::minutes(45))
+    );
+    assert_eq!(timings[1].activity, "b");
+    assert!(!timings[1].is_complete());
+    assert_eq!(timings[1].waiting_time(), None);
+}
+
+#[test]
+fn collapse_lifecycle_keeps_complete_events_with_durations() {
+    let event = |name: &str, transition: &str, time: &str| {
+        format!(
+            r#"<event><string key="concept:name" value="{name}"/><string key="lifecycle:transition" value="{transition}"/><date key="time:timestamp" value="2020-01-01T{time}Z"/></event>"#
+        )
+    };
+    let mut collapsed = trace(
+        &[
+            event("a", "start", "10:00:00"),
+            event("b", "start", "10:10:00"),
+            event("a", "complete", "10:30:00"),
+            event("c", "complete", "10:40:00"),
+            event("b", "complete", "11:10:00"),
+        ]
+        .concat(),
+    );
+    collapsed.collapse_lifecycle();
+    let names: Vec<_> = collapsed.events.iter().map(|e| e.name()).collect();
+    assert_eq!(names, [Some("a"), Some("c"), Some("b")]);
+    let durations: Vec<_> = collapsed
+        .events
+        .iter()
+        .map(|e| e.attributes.get("duration"))
+        .collect();
+    assert_eq!(
+        durations,
+        [
+            Some(&Attribute::Double(1800.0)),
+            None,
+            Some(&Attribute::Double(3600.0))
+        ]
+    );
+}
@@ -0,0 +1,149 @@
+use xes::Log;
+use xes::ValidationIssue;
+use xes::ValidationMode;
+
+fn log(text: &str) -> Log {
+    xes::read_str(text).unwrap().remove(0)
+}
+
+#[test]
+fn event_missing_concept_name_is_flagged() {
+    let log = log(r#"<log version="2.0">
+        <trace>
+            <event><string key="concept:name" value="a"/></event>
+            <event><string key="org:resource" value="r"/></event>
+        </trace>
+        <event/>
+    </log>"#);
+    let report = log.validate_required_attributes(&["concept:name"], ValidationMode::CollectAll);
+    assert!(!report.is_valid());
+    assert_eq!(
+        report.all_issues(),
+        [
+            ValidationIssue::MissingAttribute {
+                trace: Some(0),
+                event: 1,
+                key: "concept:name".to_owned(),
+            },
+            ValidationIssue::MissingAttribute {
+                trace: None,
+                event: 0,
+                key: "concept:name".to_owned(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn attribute_type_differing_from_its_global_is_flagged() {
+    let log = log(r#"<log version="2.0">
+        <global scope="event"><float key="cost:total" value="0.0"/></global>
+        <trace>
+            <event><float key="cost:total" value="1.5"/></event>
+            <event><string key="cost:total" value="expensive"/></event>
+        </trace>
+    </log>"#);
+    let report = log.validate_global_types(ValidationMode::CollectAll);
+    assert_eq!(
+        report.all_issues(),
+        [ValidationIssue::TypeMismatch {
+            trace: Some(0),
+            event: Some(1),
+            key: "cost:total".to_owned(),
+            expected: "double",
+            found: "string",
+        }]
+    );
+}
+
+#[test]
+fn fail_fast_stops_at_the_first_issue() {
+    let log = log(r#"<log version="2.0">
+        <trace><event/><event/></trace>
+        <trace><event/></trace>
+    </log>"#);
+    let all = log.validate_required_attributes(&["concept:name"], ValidationMode::CollectAll);
+    let first = log.validate_required_attributes(&["concept:name"], ValidationMode::FailFast);
+    assert_eq!(all.all_issues().len(), 3);
+    assert_eq!(first.all_issues().len(), 1);
+    assert_eq!(first.first_error(), all.first_error());
+    assert_eq!(
+        first.into_issues(),
+        [ValidationIssue::MissingAttribute {
+            trace: Some(0),
+            event: 0,
+            key: "concept:name".to_owned(),
+        }]
+    );
+    let valid = log.validate_required_attributes(&[], ValidationMode::FailFast);
+    assert!(valid.is_valid());
+    assert_eq!(valid.first_error(), None);
+}
+
+#[test]
+fn event_outside_the_declared_time_span_is_flagged() {
+    let log = log(r#"<log version="2.0">
+        <date key="time:start" value="2020-01-01T00:00:00Z"/>
+        <date key="time:end" value="2020-01-31T23:59:59Z"/>
+        <trace>
+            <event><date key="time:timestamp" value="2020-01-01T00:00:00Z"/></event>
+            <event><date key="time:timestamp" value="2020-02-01T00:00:00Z"/></event>
+            <event/>
+        </trace>
+        <event><date key="time:timestamp" value="2019-12-31T23:00:00Z"/></event>
+    </log>"#);
+    let timestamp = |value| chrono::DateTime::parse_from_rfc3339(value).unwrap();
+    assert_eq!(
+        log.validate_time_span(ValidationMode::CollectAll)
+            .all_issues(),
+        [
+            ValidationIssue::OutsideTimeSpan {
+                trace: Some(0),
+                event: 1,
+                timestamp: timestamp("2020-02-01T00:00:00Z"),
+            },
+            ValidationIssue::OutsideTimeSpan {
+                trace: None,
+                event: 0,
+                timestamp: timestamp("2019-12-31T23:00:00Z"),
+            },
+        ]
+    );
+}
+
+#[test]
+fn fail_fast_reports_the_first_mismatching_key_of_an_event() {
+    let log = log(r#"<log version="2.0">
+        <global scope="event">
+            <float key="cost:total" value="0.0"/>
+            <int key="amount" value="0"/>
+            <boolean key="urgent" value="false"/>
+        </global>
+        <trace><event>
+            <string key="urgent" value="yes"/>
+            <string key="cost:total" value="high"/>
+            <string key="amount" value="many"/>
+        </event></trace>
+    </log>"#);
+    let report = log.validate_global_types(ValidationMode::FailFast);
+    assert_eq!(
+        report.all_issues(),
+        [ValidationIssue::TypeMismatch {
+            trace: Some(0),
+            event: Some(0),
+            key: "amount".to_owned(),
+            expected: "long",
+            found: "string",
+        }]
+    );
+    let keys: Vec<_> = log
+        .validate_global_types(ValidationMode::CollectAll)
+        .into_issues()
+        .into_iter()
+        .map(|issue| match issue {
+            ValidationIssue::TypeMismatch { key, .. } => key,
+            issue => panic!("unexpected {issue:?}"),
+        })
+        .collect();
+    assert_eq!(keys, ["amount", "cost:total", "urgent"]);
+}
@@ -0,0 +1,352 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+use xes::Event;
+use xes::LineEnding;
+use xes::Log;
+use xes::NumberElements;
+use xes::ReadOptions;
+use xes::WriteOptions;
+
+fn log(text: &str) -> Log {
+    xes::read_str(text).unwrap().remove(0)
+}
+
+/// Path of a scratch file called `name`.
+fn scratch_file(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name)
+}
+
+#[test]
+fn projection_writes_only_selected_event_keys() {
+    let log = log(r#"<log version="2.0"><trace>
+        <string key="concept:name" value="case"/>
+        <event>
+            <string key="concept:name" value="a"/>
+            <date key="time:timestamp" value="2020-01-01T00:00:00Z"/>
+            <string key="org:resource" value="r"/>
+        </event>
+    </trace></log>"#);
+    let options = WriteOptions {
+        event_keys: Some(HashSet::from([
+            "concept:name".to_owned(),
+            "time:timestamp".to_owned(),
+        ])),
+        ..WriteOptions::default()
+    };
+    let projected = xes::read_str(&xes::write_string_with_options(&log, &options))
+        .unwrap()
+        .remove(0);
+    let event = &projected.traces[0].events[0];
+    let mut keys: Vec<_> = event.attributes.keys().map(|key| key.as_str()).collect();
+    keys.sort_unstable();
+    assert_eq!(keys, ["concept:name", "time:timestamp"]);
+    assert_eq!(projected.traces[0].attributes, log.traces[0].attributes);
+}
+
+#[test]
+fn special_characters_are_escaped() {
+    let log = log(r#"<log version="2.0"><trace><event>
+        <string key="a&amp;b" value="&lt;x&gt; &quot;y&quot; 'z' &amp;"/>
+        <long key="n" value="-12"/>
+        <double key="d" value="0.5"/>
+        <boolean key="b" value="true"/>
+    </event></trace></log>"#);
+    let written = xes::write_string(&log);
+    assert!(written
+        .contains(r#"<string key="a&amp;b" value="&lt;x&gt; &quot;y&quot; &apos;z&apos; &amp;">"#));
+    assert_eq!(xes::read_str(&written).unwrap(), [log]);
+}
+
+#[test]
+fn sorted_attributes_are_written_in_key_order() {
+    let log = log(r#"<log version="2.0"><trace><event>
+        <string key="c" value="3"/><string key="a" value="1"/>
+        <string key="d" value="4"/><string key="b" value="2"/>
+    </event></trace></log>"#);
+    let options = WriteOptions {
+        sort_attributes: true,
+        ..WriteOptions::default()
+    };
+    let written = xes::write_string_with_options(&log, &options);
+    let event = &written[written.find("<event>").unwrap()..];
+    assert!(event.starts_with(concat!(
+        r#"<event><string key="a" value="1"></string><string key="b" value="2"></string>"#,
+        r#"<string key="c" value="3"></string><string key="d" value="4"></string></event>"#
+    )));
+}
+
+#[test]
+fn whitespace_in_values_survives_a_round_trip() {
+    let log = log(concat!(
+        r#"<log version="2.0"><trace><event>"#,
+        r#"<string key="padded" value="  padded  "/>"#,
+        "<string key=\"text\">line1\nline2\tx</string>",
+        r#"<string key="reference" value="p&#10;q&#13;&#9;r"/>"#,
+        r#"</event></trace></log>"#
+    ));
+    let event = &log.traces[0].events[0];
+    assert_eq!(event.attributes["padded"].as_str(), Some("  padded  "));
+    assert_eq!(event.attributes["text"].as_str(), Some("line1\nline2\tx"));
+    assert_eq!(event.attributes["reference"].as_str(), Some("p\nq\r\tr"));
+    let reread = xes::read_str(&xes::write_string(&log)).unwrap();
+    assert_eq!(reread, [log]);
+}
+
+#[test]
+fn appended_events_are_read_back() {
+    let path = scratch_file("append_events.xes");
+    let text = r#"<log version="2.0"><event><string key="concept:name" value="a"/></event></log>"#;
+    std::fs::write(&path, format!("{text}\n\n")).unwrap();
+    let appended = log(r#"<log version="2.0">
+        <event><string key="concept:name" value="b"/></event>
+        <event><string key="concept:name" value="c"/></event>
+    </log>"#)
+    .events;
+    xes::append_events(&path, &appended).unwrap();
+    let log = xes::read(&path).unwrap().remove(0);
+    let names: Vec<_> = log.events.iter().map(Event::name).collect();
+    assert_eq!(names, [Some("a"), Some("b"), Some("c")]);
+    assert!(std::fs::read_to_string(&path)
+        .unwrap()
+        .ends_with("</log>\n\n"));
+}
+
+/// Events named `names` directly under a log.
+fn loose_events(names: &[&str]) -> Vec<Event> {
+    let mut text = String::from(r#"<log version="2.0">"#);
+    for name in names {
+        text.push_str(&format!(
+            r#"<event><string key="concept:name" value="{name}"/></event>"#
+        ));
+    }
+    log(&(text + "</log>")).events
+}
+
+#[test]
+fn events_are_appended_to_a_self_closing_log() {
+    let path = scratch_file("append_events_empty.xes");
+    std::fs::write(&path, "<xes:log xmlns:xes=\"x\" version=\"2.0\" />\n").unwrap();
+    xes::append_events(&path, &loose_events(&["a", "b"])).unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(text.ends_with("</xes:log>\n"), "{text}");
+    let log = xes::read_str(&text).unwrap().remove(0);
+    let names: Vec<_> = log.events.iter().map(Event::name).collect();
+    assert_eq!(names, [Some("a"), Some("b")]);
+}
+
+#[test]
+fn end_tags_in_trailing_comments_are_not_appended_to() {
+    let path = scratch_file("append_events_comment.xes");
+    let text = "<log version=\"2.0\"><event/></log >\n<!-- was </log> -->\n";
+    std::fs::write(&path, text).unwrap();
+    xes::append_events(&path, &loose_events(&["b"])).unwrap();
+    let written = std::fs::read_to_string(&path).unwrap();
+    assert!(
+        written.ends_with("</log >\n<!-- was </log> -->\n"),
+        "{written}"
+    );
+    assert_eq!(xes::read_str(&written).unwrap()[0].events.len(), 2);
+    let empty = scratch_file("append_events_none.xes");
+    std::fs::write(&empty, "<export/>").unwrap();
+    assert!(matches!(
+        xes::append_events(&empty, &loose_events(&["b"])),
+        Err(xes::XesError::NoLogs)
+    ));
+}
+
+#[test]
+fn classifiers_survive_a_round_trip() {
+    let log = log(r#"<log version="2.0">
+        <classifier name="Activity" keys="concept:name  lifecycle:transition"/>
+        <classifier name="Case" scope="trace" keys="'case type' concept:name"/>
+    </log>"#);
+    let keys: Vec<_> = log.classifiers.iter().map(|c| c.keys.clone()).collect();
+    assert_eq!(
+        keys,
+        [
+            ["concept:name", "lifecycle:transition"],
+            ["case type", "concept:name"],
+        ]
+    );
+    assert_eq!(log.classifiers[1].scope.as_deref(), Some("trace"));
+    let written = xes::write_string(&log);
+    assert!(written.contains(r#"keys="&apos;case type&apos; concept:name""#));
+    assert_eq!(xes::read_str(&written).unwrap(), [log]);
+}
+
+#[test]
+fn schema_location_survives_a_round_trip() {
+    let text = concat!(
+        r#"<log xes.version="1.0" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" "#,
+        r#"xsi:schemaLocation="http://www.xes-standard.org/ xes.xsd" version="1.0" features=""/>"#
+    );
+    let options = xes::ReadOptions {
+        keep_log_xml_attributes: true,
+        ..xes::ReadOptions::default()
+    };
+    let log = xes::read_str_with_options(text, &options)
+        .unwrap()
+        .remove(0);
+    let written = xes::write_string(&log);
+    assert!(written.starts_with(concat!(
+        r#"<log version="1.0" features="" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" "#,
+        r#"xes.version="1.0" xsi:schemaLocation="http://www.xes-standard.org/ xes.xsd">"#
+    )));
+    let reread = xes::read_str_with_options(&written, &options).unwrap();
+    assert_eq!(reread, [log]);
+}
+
+#[test]
+fn number_element_names_follow_the_option() {
+    let log = log(r#"<log version="2.0"><trace><event>
+        <long key="n" value="3"/><double key="d" value="0.25"/>
+        <datetime key="time:timestamp" value="2020-01-01T00:00:00Z"/>
+    </event></trace></log>"#);
+    let write = |number_elements| {
+        let options = WriteOptions {
+            number_elements,
+            ..WriteOptions::default()
+        };
+        xes::write_string_with_options(&log, &options)
+    };
+    let standard = write(NumberElements::Standard);
+    assert!(standard.contains(r#"<int key="n" value="3"></int>"#));
+    assert!(standard.contains(r#"<float key="d" value="0.25"></float>"#));
+    assert!(!standard.contains("<long") && !standard.contains("<double"));
+    let legacy = write(NumberElements::Legacy);
+    assert!(legacy.contains(r#"<long key="n" value="3"></long>"#));
+    assert!(legacy.contains(r#"<double key="d" value="0.25"></double>"#));
+    assert!(!legacy.contains("<int") && !legacy.contains("<float"));
+    for written in [standard, legacy] {
+        assert!(written.contains(r#"<date key="time:timestamp" "#));
+        assert!(!written.contains("<datetime"));
+        assert_eq!(xes::read_str(&written).unwrap()[0], log);
+    }
+}
+
+#[test]
+fn id_values_survive_a_round_trip_unchanged() {
+    let id = "{0F8FAD5B-D9CB-469F-A165-70867728950E}";
+    let log = log(&format!(
+        r#"<log version="2.0"><trace><event><id key="identity:id" value="{id}"/></event></trace></log>"#
+    ));
+    assert_eq!(log.traces[0].events[0].id(), Some(id));
+    let written = xes::write_string(&log);
+    assert!(written.contains(&format!(r#"<id key="identity:id" value="{id}">"#)));
+    assert_eq!(xes::read_str(&written).unwrap()[0], log);
+}
+
+#[test]
+fn key_is_written_before_value() {
+    let log = log(r#"<log version="2.0">
+        <global scope="event"><string value="?" key="concept:name"/></global>
+        <trace><event>
+            <string value="a" key="concept:name"/>
+            <date value="2020-01-01T00:00:00Z" key="time:timestamp"/>
+            <int value="1" key="n"/>
+            <float value="1.5" key="x"/>
+            <boolean value="true" key="done"/>
+            <id value="i" key="identity:id"/>
+            <list key="l"><string value="v" key="k"/></list>
+        </event></trace>
+    </log>"#);
+    let written = xes::write_string(&log);
+    let mut checked = 0;
+    for tag in written.split('<').filter(|tag| tag.contains(" value=\"")) {
+        let key = tag.find(" key=\"").expect("scalar has a key");
+        assert!(key < tag.find(" value=\"").unwrap(), "{tag}");
+        checked += 1;
+    }
+    assert_eq!(checked, 8);
+}
+
+#[test]
+fn indented_output_uses_the_chosen_line_ending() {
+    let log = log(
+        r#"<log version="2.0"><trace><event><string key="c" value="E"/></event></trace></log>"#,
+    );
+    let options = WriteOptions {
+        indent: Some(2),
+        line_ending: LineEnding::CrLf,
+        ..WriteOptions::default()
+    };
+    let crlf = xes::write_string_with_options(&log, &options);
+    assert_eq!(
+        crlf,
+        "<log version=\"2.0\" features=\"\">\r\n  <trace>\r\n    <event>\r\n      \
+         <string key=\"c\" value=\"E\"></string>\r\n    </event>\r\n  </trace>\r\n</log>"
+    );
+    let lf = xes::write_string_with_options(
+        &log,
+        &WriteOptions {
+            line_ending: LineEnding::Lf,
+            ..options
+        },
+    );
+    assert_eq!(lf, crlf.replace("\r\n", "\n"));
+    assert_eq!(xes::read_str(&crlf).unwrap()[0], log);
+}
+
+#[test]
+fn log_element_attributes_are_written_in_a_fixed_order() {
+    let options = ReadOptions {
+        keep_log_xml_attributes: true,
+        ..ReadOptions::default()
+    };
+    let log = xes::read_str_with_options(
+        r#"<log xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="x"
+            features="nested-attributes" xmlns="http://www.xes-standard.org/" version="1.0"/>"#,
+        &options,
+    )
+    .unwrap()
+    .remove(0);
+    let written = xes::write_string(&log);
+    let start = &written[..=written.find('>').unwrap()];
+    assert_eq!(
+        start,
+        r#"<log version="1.0" features="nested-attributes" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns="http://www.xes-standard.org/" xsi:schemaLocation="x">"#
+    );
+}
+
+#[test]
+fn omitted_defaults_reappear_when_globals_are_resolved() {
+    let log = log(r#"<log version="2.0">
+        <global scope="trace"><string key="source" value="erp"/></global>
+        <global scope="event">
+            <string key="org:resource" value="unknown"/>
+            <string key="lifecycle:transition" value="complete"/>
+        </global>
+        <trace>
+            <string key="source" value="erp"/>
+            <event>
+                <string key="org:resource" value="unknown"/>
+                <string key="lifecycle:transition" value="complete"/>
+            </event>
+            <event>
+                <string key="org:resource" value="ann"/>
+                <string key="lifecycle:transition" value="complete"/>
+            </event>
+        </trace>
+    </log>"#);
+    let options = WriteOptions {
+        omit_defaults: true,
+        ..WriteOptions::default()
+    };
+    let written = xes::write_string_with_options(&log, &options);
+    assert_eq!(written.matches(r#"value="unknown""#).count(), 1);
+    assert_eq!(written.matches(r#"value="complete""#).count(), 1);
+    assert_eq!(written.matches(r#"value="erp""#).count(), 1);
+    assert!(written.contains(r#"value="ann""#));
+    let sparse = xes::read_str(&written).unwrap().remove(0);
+    assert!(sparse.traces[0].events[0].attributes.is_empty());
+    let resolved = xes::read_str_with_options(
+        &written,
+        &ReadOptions {
+            resolve_globals: true,
+            ..ReadOptions::default()
+        },
+    )
+    .unwrap()
+    .remove(0);
+    assert_eq!(resolved, log);
+}